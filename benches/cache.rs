@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use stitch::compilation::compiler::Compiler;
+
+const FILES: usize = 40;
+const SENTENCES: usize = 400;
+const ROUNDS: usize = 15;
+// The warm median must stay under this share of the cold one, so noise alone does not fail the run
+const MARGIN: f64 = 0.9;
+
+fn main() {
+    let project = env::temp_dir().join("stitch-bench-cache");
+    if project.exists() {
+        fs::remove_dir_all(&project).expect("Failed to remove the previous benchmark project");
+    }
+    create_project(&project.join("source"));
+
+    // Cold and warm rounds take turns, so a change in the machine load hits both alike
+    let (cold, warm) = medians(
+        || {
+            Compiler::clean(&project, false).expect("Failed to clean the intermediate directory");
+            tokenize(&project)
+        },
+        || tokenize(&project),
+    );
+
+    println!("cold build: {:?}", cold);
+    println!("warm build: {:?}", warm);
+
+    fs::remove_dir_all(&project).expect("Failed to remove the benchmark project");
+    assert!(warm < cold.mul_f64(MARGIN), "The warm build ({:?}) is not measurably faster than the cold build ({:?})", warm, cold);
+}
+
+fn create_project(source: &Path) {
+    fs::create_dir_all(source).expect("Failed to create the benchmark sources");
+
+    let sentence = "so greeting is text as (\"hello\" uppercase). greeting as (greeting substring [0], [3]).\n";
+    for index in 0..FILES {
+        let path = source.join(format!("file{index}.prs"));
+        fs::write(path, sentence.repeat(SENTENCES)).expect("Failed to write a benchmark source");
+    }
+}
+
+fn tokenize(project: &Path) -> Duration {
+    let start = Instant::now();
+    Compiler::new(project)
+        .and_then(Compiler::tokenize)
+        .expect("Failed to tokenize the benchmark project");
+    start.elapsed()
+}
+
+fn medians(mut first: impl FnMut() -> Duration, mut second: impl FnMut() -> Duration) -> (Duration, Duration) {
+    // The first rounds only warm up the file system
+    first();
+    second();
+    let (mut firsts, mut seconds) = (Vec::new(), Vec::new());
+    for _ in 0..ROUNDS {
+        firsts.push(first());
+        seconds.push(second());
+    }
+    firsts.sort();
+    seconds.sort();
+    (firsts[ROUNDS / 2], seconds[ROUNDS / 2])
+}
@@ -0,0 +1,94 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use stitch::compilation::compiler::Compiler;
+
+const FILES: usize = 40;
+const SENTENCES: usize = 400;
+const ROUNDS: usize = 15;
+// The parallel median must stay under this share of the sequential one, so noise alone does not fail the run
+const MARGIN: f64 = 0.9;
+
+fn main() {
+    let project = env::temp_dir().join("stitch-bench-jobs");
+    if project.exists() {
+        fs::remove_dir_all(&project).expect("Failed to remove the previous benchmark project");
+    }
+    create_project(&project.join("source"));
+
+    let jobs = thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1);
+
+    // Both the freshly scanned and the cached tokens must come out in the same order,
+    // which shows in the program parsed from them
+    Compiler::clean(&project, false).expect("Failed to clean the intermediate directory");
+    let sequential = tokenize(&project, 1).1;
+    Compiler::clean(&project, false).expect("Failed to clean the intermediate directory");
+    let parallel = tokenize(&project, jobs.max(2)).1;
+    let cached = tokenize(&project, jobs.max(2)).1;
+    assert!(sequential == parallel, "Parallel tokenization changed the tokens");
+    assert!(sequential == cached, "Cached parallel tokenization changed the tokens");
+
+    // Sequential and parallel rounds take turns, so a change in the machine load hits both alike
+    let (single, multiple) = medians(
+        || {
+            Compiler::clean(&project, false).expect("Failed to clean the intermediate directory");
+            tokenize(&project, 1).0
+        },
+        || {
+            Compiler::clean(&project, false).expect("Failed to clean the intermediate directory");
+            tokenize(&project, jobs).0
+        },
+    );
+
+    println!("cold build with 1 job: {:?}", single);
+    println!("cold build with {} jobs: {:?}", jobs, multiple);
+
+    fs::remove_dir_all(&project).expect("Failed to remove the benchmark project");
+    // A single core cannot gain anything from more threads, so the numbers are only reported
+    if jobs > 1 {
+        assert!(multiple < single.mul_f64(MARGIN), "The build with {} jobs ({:?}) is not measurably faster than with 1 job ({:?})", jobs, multiple, single);
+    }
+}
+
+fn create_project(source: &Path) {
+    fs::create_dir_all(source).expect("Failed to create the benchmark sources");
+
+    let sentence = "so greeting is text as (\"hello\" uppercase). greeting as (greeting substring [0], [3]).\n";
+    for index in 0..FILES {
+        let path = source.join(format!("file{index}.prs"));
+        // Each file gets its own variable, so a misplaced file shows up in the token order
+        let content = sentence.replace("greeting", &format!("greeting{index}"));
+        fs::write(path, content.repeat(SENTENCES)).expect("Failed to write a benchmark source");
+    }
+}
+
+fn tokenize(project: &Path, jobs: usize) -> (Duration, String) {
+    let start = Instant::now();
+    let compiler = Compiler::new(project)
+        .map(|compiler| compiler.jobs(jobs))
+        .and_then(Compiler::tokenize)
+        .expect("Failed to tokenize the benchmark project");
+    let elapsed = start.elapsed();
+
+    let program = compiler.parse(usize::MAX)
+        .expect("Failed to parse the benchmark project")
+        .program();
+    (elapsed, program.to_string())
+}
+
+fn medians(mut first: impl FnMut() -> Duration, mut second: impl FnMut() -> Duration) -> (Duration, Duration) {
+    // The first rounds only warm up the file system
+    first();
+    second();
+    let (mut firsts, mut seconds) = (Vec::new(), Vec::new());
+    for _ in 0..ROUNDS {
+        firsts.push(first());
+        seconds.push(second());
+    }
+    firsts.sort();
+    seconds.sort();
+    (firsts[ROUNDS / 2], seconds[ROUNDS / 2])
+}
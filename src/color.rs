@@ -0,0 +1,82 @@
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ColorChoice {
+    /// Color only when errors are printed to a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+pub fn init(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Auto => io::stderr().is_terminal(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn error(message: &str) -> String {
+    paint(message, ENABLED.load(Ordering::Relaxed))
+}
+
+// Paints the error red, with each `[line N]` location in yellow
+fn paint(message: &str, enabled: bool) -> String {
+    if !enabled {
+        return message.to_string();
+    }
+
+    let mut painted = String::from(RED);
+    let mut rest = message;
+    while let Some(start) = rest.find("[line ") {
+        let Some(length) = rest[start..].find(']') else { break };
+        let end = start + length + 1;
+        painted.push_str(&rest[..start]);
+        painted.push_str(&format!("{YELLOW}{}{RED}", &rest[start..end]));
+        rest = &rest[end..];
+    }
+    painted.push_str(rest);
+    painted.push_str(RESET);
+    painted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "[E0005] Parsed with error(s): [line 3] Error at '}'";
+
+    #[test]
+    fn never_is_plain() {
+        assert_eq!(paint(MESSAGE, false), MESSAGE);
+        assert!(!paint(MESSAGE, false).contains('\x1b'));
+    }
+
+    #[test]
+    fn always_paints_the_line() {
+        let painted = paint(MESSAGE, true);
+        assert!(painted.starts_with(RED));
+        assert!(painted.contains(&format!("{YELLOW}[line 3]{RED}")));
+        assert!(painted.ends_with(RESET));
+    }
+
+    // The only test touching the global choice, so it cannot race with another
+    #[test]
+    fn init_follows_the_choice() {
+        init(ColorChoice::Never);
+        assert_eq!(error(MESSAGE), MESSAGE);
+        init(ColorChoice::Always);
+        assert_eq!(error(MESSAGE), paint(MESSAGE, true));
+        init(ColorChoice::Never);
+    }
+}
@@ -1,50 +1,169 @@
-use stitch::compilation::compiler::Compiler;
+use std::fs;
+use std::path::Path;
+
+use stitch::compilation::compiler::{Compiler, Evaluated, Parsed, Ready};
+use stitch::compilation::errors::CompilerError;
+use stitch::compilation::errors::ErrorCode;
+use stitch::compilation::parser::DEFAULT_MAX_ERRORS;
 use stitch::projects::{config::Config, project::Project};
 
-pub fn create_project(name: &str) {
-    let result = Config::create(name)
-        .and_then(|_| Project::create_entrypoint());
+use crate::color;
+use crate::{Emit, Target};
+
+pub fn create_project(root: &Path, name: &str) {
+    let result = Config::create(root, name)
+        .and_then(|_| Project::create_entrypoint(root));
     
     if let Err(error) = result {
-        eprintln!("{}", error);
+        eprintln!("{}", color::error(&error.to_string()));
     }
 }
 
-pub fn build_project() {
-    let result = Compiler::new()
+// The warnings about the sources found are printed, or fail the build when strict
+fn find_sources(root: &Path, strict: bool) -> Result<Compiler<Ready>, CompilerError> {
+    let compiler = Compiler::new(root)?;
+    if strict && !compiler.warnings().is_empty() {
+        let error = compiler.warnings().iter().fold(CompilerError::None, |error, warning| {
+            error.add(CompilerError::PromotedWarning(warning.clone()))
+        });
+        return Err(error);
+    }
+
+    for warning in compiler.warnings() {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(compiler)
+}
+
+fn parse_project(root: &Path, max_errors: usize, jobs: usize, strict: bool, bare_numbers: bool) -> Result<Compiler<Parsed>, CompilerError> {
+    find_sources(root, strict)
+        .map(|compiler| compiler.jobs(jobs).bare_numbers(bare_numbers))
         .and_then(Compiler::tokenize)
-        .and_then(Compiler::parse)
-        .and_then(Compiler::evaluate);
+        .map(|compiler| compiler.strict(strict))
+        .and_then(|compiler| compiler.parse(max_errors))
+}
+
+pub fn build_project(root: &Path, max_errors: usize, target: Option<Target>, jobs: usize, strict: bool, bare_numbers: bool) {
+    let result = parse_project(root, max_errors, jobs, strict, bare_numbers)
+        .and_then(|compiler| match target {
+            Some(Target::Ir) => {
+                for operation in compiler.lower().iter() {
+                    println!("{}", operation);
+                }
+                Ok(())
+            },
+            None => compiler.evaluate().map(|_| ()),
+        });
 
     if let Err(error) = result {
-        eprintln!("{}", error);
+        eprintln!("{}", color::error(&error.to_string()));
     }
 }
 
-pub fn clean_project() {
-    let result = Compiler::clean();
+pub fn check_project(root: &Path, jobs: usize, strict: bool, bare_numbers: bool) {
+    let result = parse_project(root, DEFAULT_MAX_ERRORS, jobs, strict, bare_numbers)
+        .and_then(|compiler| compiler.check());
 
-    if let Err(error) = result {
-        eprintln!("{}", error);
+    match result {
+        Ok(()) => println!("No type errors found"),
+        Err(error) => eprintln!("{}", color::error(&error.to_string())),
+    }
+}
+
+pub fn clean_project(root: &Path, force: bool) {
+    match Compiler::clean(root, force) {
+        Ok(cleaned) => println!("Removed {} file(s), {} bytes", cleaned.files, cleaned.bytes),
+        Err(error) => eprintln!("{}", color::error(&error.to_string())),
     }
 }
 
-pub fn clean_and_build_project() {
-    let result = Compiler::clean()
-        .and_then(|_| Compiler::new())
+pub fn clean_and_build_project(root: &Path, max_errors: usize, force: bool, jobs: usize, strict: bool, bare_numbers: bool) {
+    let result = Compiler::clean(root, force)
+        .and_then(|_| find_sources(root, strict))
+        .map(|compiler| compiler.jobs(jobs).bare_numbers(bare_numbers))
         .and_then(Compiler::tokenize)
-        .and_then(Compiler::parse)
+        .map(|compiler| compiler.strict(strict))
+        .and_then(|compiler| compiler.parse(max_errors))
         .and_then(Compiler::evaluate);
 
     if let Err(error) = result {
-        eprintln!("{}", error);
+        eprintln!("{}", color::error(&error.to_string()));
     }
 }
 
-pub fn run_project() {
-    
+pub fn run_project(root: &Path, emit: Option<Emit>, trace: bool, dump_env: bool, jobs: usize, strict: bool, bare_numbers: bool) {
+    let result = parse_project(root, DEFAULT_MAX_ERRORS, jobs, strict, bare_numbers)
+        .map(|compiler| compiler.trace(trace))
+        .and_then(|compiler| match emit {
+            Some(Emit::Ast) => {
+                print!("{}", compiler.program());
+                Ok(())
+            },
+            Some(Emit::ValuesJson) => compiler.evaluate()
+                .inspect(|compiler| dump_environment(compiler, dump_env))
+                .and_then(|compiler| compiler.values_json())
+                .map(|json| println!("{}", json)),
+            None => compiler.evaluate().map(|compiler| {
+                for value in compiler.values() {
+                    println!("{}", value);
+                }
+                dump_environment(&compiler, dump_env);
+            }),
+        });
+
+    if let Err(error) = result {
+        eprintln!("{}", color::error(&error.to_string()));
+    }
+}
+
+// Written to stderr like the trace, so it stays out of the emitted values
+fn dump_environment(compiler: &Compiler<Evaluated>, dump_env: bool) {
+    if dump_env {
+        eprint!("{}", compiler.environment());
+    }
+}
+
+// The sources are scanned straight into the parser, so the intermediate files are never read
+pub fn build_and_run_project(root: &Path, max_errors: usize, trace: bool, strict: bool, bare_numbers: bool) {
+    let result = find_sources(root, strict)
+        .map(|compiler| compiler.bare_numbers(bare_numbers))
+        .and_then(Compiler::scan)
+        .map(|compiler| compiler.strict(strict))
+        .and_then(|compiler| compiler.parse(max_errors))
+        .and_then(|compiler| compiler.trace(trace).evaluate())
+        .map(|compiler| {
+            for value in compiler.values() {
+                println!("{}", value);
+            }
+        });
+
+    if let Err(error) = result {
+        eprintln!("{}", color::error(&error.to_string()));
+    }
+}
+
+// The definitions and their docs are written as markdown, nothing is evaluated
+pub fn document_project(root: &Path, jobs: usize, strict: bool, bare_numbers: bool) {
+    let result = parse_project(root, DEFAULT_MAX_ERRORS, jobs, strict, bare_numbers)
+        .and_then(|compiler| {
+            let path = Project::get_docs_dir(root, true)?.join("definitions.md");
+            fs::write(&path, compiler.program().markdown("Definitions"))?;
+            Ok(path)
+        });
+
+    match result {
+        Ok(path) => println!("Documented the definitions in {}", path.display()),
+        Err(error) => eprintln!("{}", color::error(&error.to_string())),
+    }
 }
 
 pub fn test_project() {
     
 }
+
+pub fn explain_error(code: &str) {
+    match ErrorCode::find(code) {
+        Some(code) => println!("{}\n\n{}", code, code.explain()),
+        None => eprintln!("{}", color::error(&format!("Unknown error code {}", code))),
+    }
+}
@@ -0,0 +1,276 @@
+use std::rc::Rc;
+
+use crate::compilation::builtins::is_builtin;
+use crate::compilation::conjunction::Conjunction;
+use crate::compilation::datatype::Datatype;
+use crate::compilation::environment::is_reserved;
+use crate::compilation::errors::CompilerError;
+use crate::compilation::phrase::Phrase;
+use crate::compilation::prefix::Prefix;
+use crate::compilation::primitive::Primitive;
+use crate::compilation::statement::Statement;
+use crate::compilation::verb::Verb;
+
+pub fn analyse(statements: &[Statement]) -> Vec<Rc<str>> {
+    let mut warnings = Vec::new();
+    for statement in statements {
+        analyse_statement(statement, &mut warnings);
+    }
+    warnings
+}
+
+fn analyse_statement(statement: &Statement, warnings: &mut Vec<Rc<str>>) {
+    find_shadowed_builtin(statement, warnings);
+
+    match statement {
+        Statement::Noun { body, .. } => {
+            for definition in body.0.iter() {
+                analyse_statement(definition, warnings);
+            }
+        },
+        Statement::Verb { name, body, .. } => find_unreachable(&format!("verb {name}"), &body.0, warnings),
+        Statement::Adjective { name, body, .. } => find_unreachable(&format!("adjective {name}"), &body.0, warnings),
+        _ => {},
+    }
+}
+
+fn find_shadowed_builtin(statement: &Statement, warnings: &mut Vec<Rc<str>>) {
+    let (kind, names) = match statement {
+        Statement::Noun { name, .. } => ("noun", std::slice::from_ref(name)),
+        Statement::Verb { name, .. } => ("verb", std::slice::from_ref(name)),
+        Statement::Adjective { name, .. } => ("adjective", std::slice::from_ref(name)),
+        Statement::So { names, .. } => ("so", names.as_ref()),
+        _ => return,
+    };
+
+    for name in names {
+        if is_builtin(name) {
+            let msg = format!("The {kind} definition \"{name}\" shadows the builtin verb \"{name}\"");
+            warnings.push(msg.into());
+        }
+    }
+}
+
+fn find_unreachable(routine: &str, body: &[Statement], warnings: &mut Vec<Rc<str>>) {
+    // A hence qualified with `when` may not conclude, so only a plain hence ends the body
+    let conclusion = body.iter().position(|statement| match statement {
+        Statement::Hence(Some(Phrase::Postfix { .. })) => false,
+        Statement::Hence(_) => true,
+        _ => false,
+    });
+
+    if let Some(index) = conclusion {
+        for statement in &body[index + 1..] {
+            let msg = format!("Unreachable sentence \"{statement}\" after hence in {routine}");
+            warnings.push(msg.into());
+        }
+    }
+}
+
+// The parser should never leave a None sentinel in the tree, finding one means a parser bug
+pub fn validate(statements: &[Statement]) -> Result<(), CompilerError> {
+    let mut error = CompilerError::None;
+    for statement in statements {
+        // Only a verb or adjective body has something to conclude
+        if let Statement::Hence(_) = statement {
+            let msg = format!("\"{statement}\" is outside of a verb or adjective");
+            error = error.add(CompilerError::SemanticError(msg.into()));
+        }
+        validate_statement(statement, &mut error);
+    }
+
+    match error {
+        CompilerError::None => Ok(()),
+        error => Err(error),
+    }
+}
+
+fn validate_statement(statement: &Statement, error: &mut CompilerError) {
+    match statement {
+        Statement::Noun { body, .. } => {
+            for inner in body.0.iter() {
+                validate_statement(inner, error);
+            }
+        },
+        Statement::Adjective { name, body, .. } => {
+            for inner in body.0.iter() {
+                validate_statement(inner, error);
+            }
+
+            // An adjective always concludes with a notion
+            if body.0.iter().any(|inner| matches!(inner, Statement::Hence(None))) {
+                let msg = format!("Adjective {name} is {} but concludes without a value", Datatype::Boolean);
+                *error = error.clone().add(CompilerError::SemanticError(msg.into()));
+            }
+        },
+        Statement::Verb { name, hence_type, object_types, body, .. } => {
+            for inner in object_types.iter().chain(body.0.iter()) {
+                validate_statement(inner, error);
+            }
+
+            // Only a verb without a hence type may conclude without a value
+            if let Some(hencetype) = hence_type {
+                if body.0.iter().any(|inner| matches!(inner, Statement::Hence(None))) {
+                    let msg = format!("Verb {name} is {hencetype} but concludes without a value");
+                    *error = error.clone().add(CompilerError::SemanticError(msg.into()));
+                }
+            }
+        },
+        Statement::So { names, initializer, .. } => {
+            for name in names.iter().filter(|name| is_reserved(name)) {
+                let msg = format!("Unable to declare reserved name \"{name}\" in \"{statement}\"");
+                *error = error.clone().add(CompilerError::SemanticError(msg.into()));
+            }
+            if let Some(phrase) = initializer {
+                validate_phrase(phrase, statement, error);
+            }
+        },
+        Statement::Hence(None) => {},
+        Statement::Phrase(phrase) | Statement::Hence(Some(phrase)) => validate_phrase(phrase, statement, error),
+    }
+}
+
+fn validate_phrase(phrase: &Phrase, statement: &Statement, error: &mut CompilerError) {
+    let sentinel = match phrase {
+        Phrase::None => Some("phrase"),
+        Phrase::Prefix { prefix: Prefix::None, .. } => Some("prefix"),
+        Phrase::Action { verb: Verb::None, .. } => Some("verb"),
+        Phrase::Condition { conjunction: Conjunction::None, .. } => Some("conjunction"),
+        _ => None,
+    };
+
+    if let Some(kind) = sentinel {
+        let msg = format!("Unexpected empty {kind} in \"{statement}\"");
+        *error = error.clone().add(CompilerError::SemanticError(msg.into()));
+    }
+
+    // `it` and `super` are set by the interpreter, reading them is fine but assigning them is not
+    let assigned = match phrase {
+        Phrase::Action { subject: Some(subject), verb: Verb::Assign, .. } => match subject.as_ref() {
+            Phrase::Primary(Primitive::It) => Some("it"),
+            Phrase::Primary(Primitive::Variable(name)) if is_reserved(name) => Some(name.as_ref()),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(name) = assigned {
+        let msg = format!("Unable to assign to reserved name \"{name}\" in \"{statement}\"");
+        *error = error.clone().add(CompilerError::SemanticError(msg.into()));
+    }
+
+    match phrase {
+        Phrase::Primary(Primitive::Collective(phrases)) => {
+            for inner in phrases.iter() {
+                validate_phrase(inner, statement, error);
+            }
+        },
+        Phrase::Postfix { noun, adjective } => {
+            validate_phrase(noun, statement, error);
+            validate_phrase(adjective, statement, error);
+        },
+        Phrase::Prefix { noun, .. } => validate_phrase(noun, statement, error),
+        Phrase::Action { subject, object, .. } => {
+            for inner in [subject, object].into_iter().flatten() {
+                validate_phrase(inner, statement, error);
+            }
+        },
+        Phrase::Condition { left, right, .. } => {
+            validate_phrase(left, statement, error);
+            validate_phrase(right, statement, error);
+        },
+        Phrase::None | Phrase::Primary(_) => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::errors::ErrorCode;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+    use crate::compilation::statement::Statements;
+
+    fn parse(source: &str) -> Rc<[Statement]> {
+        let scanner = Scanner::new(source, Rc::from([])).tokenize();
+        Parser::new(scanner.tokens().into())
+            .parse()
+            .unwrap_or_else(|error| panic!("Failed to parse {source:?}: {error}"))
+            .statements()
+    }
+
+    #[test]
+    fn sentence_after_hence_is_unreachable() {
+        let warnings = analyse(&parse("verb twice is number for number { hence it * [2]. it + [1]. }"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Unreachable sentence \"it + [1.0].\" after hence in verb twice"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn hence_at_the_end_is_fine() {
+        assert!(analyse(&parse("verb twice is number for number { it print. hence it * [2]. }")).is_empty());
+        assert!(analyse(&parse("verb half is number for number { hence [0] when it < [1]. hence it / [2]. }")).is_empty());
+    }
+
+    #[test]
+    fn empty_verb_is_caught() {
+        let number = |value: &str| Some(Box::new(Phrase::Primary(Primitive::Number(value.into()))));
+        let statements = [Statement::Phrase(Phrase::Action { subject: number("1"), verb: Verb::None, object: number("2") })];
+
+        match validate(&statements) {
+            Err(CompilerError::SemanticError(message)) => assert!(message.contains("Unexpected empty verb"), "{message}"),
+            other => panic!("Expected a semantic error but found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_sentinels_are_caught_when_nested() {
+        let statements = [Statement::Verb {
+            name: "broken".into(),
+            hence_type: None,
+            subject_type: None,
+            object_types: Rc::from([]),
+            body: Statements(Rc::from([Statement::Hence(Some(Phrase::Prefix { prefix: Prefix::None, noun: Box::new(Phrase::None) }))])),
+            doc: None,
+        }];
+
+        let error = validate(&statements).expect_err("The verb body holds two sentinels");
+        assert!(error.to_string().contains("Unexpected empty prefix"), "{error}");
+        assert!(error.to_string().contains("Unexpected empty phrase"), "{error}");
+        assert!(error.to_string().contains(&ErrorCode::InvalidStructure.to_string()), "{error}");
+        assert!(validate(&parse("so x is number as [1] + [2]. x.")).is_ok());
+    }
+
+    #[test]
+    fn bare_hence_needs_an_untyped_verb() {
+        assert!(validate(&parse("verb greet { \"hi\" print. hence. }")).is_ok());
+
+        for (source, expected) in [
+            ("verb twice is number for number { hence. }", "Verb twice is number but concludes without a value"),
+            ("adjective big for number { hence. }", "Adjective big is notion but concludes without a value"),
+        ] {
+            match validate(&parse(source)) {
+                Err(CompilerError::SemanticError(message)) => assert!(message.contains(expected), "{message}"),
+                other => panic!("Expected a semantic error for {source:?} but found {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn so_shadowing_a_builtin_is_warned() {
+        let warnings = analyse(&parse("so reverse as [1]."));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("The so definition \"reverse\" shadows the builtin verb \"reverse\""), "{}", warnings[0]);
+        assert!(analyse(&parse("so backwards as [1].")).is_empty());
+    }
+
+    #[test]
+    fn hence_outside_a_body_is_rejected() {
+        for source in ["hence.", "hence [5]."] {
+            match validate(&parse(source)) {
+                Err(CompilerError::SemanticError(message)) => assert!(message.contains("is outside of a verb or adjective"), "{message}"),
+                other => panic!("Expected a semantic error for {source:?} but found {other:?}"),
+            }
+        }
+        assert!(validate(&parse("verb five is number { hence [5]. }")).is_ok());
+    }
+}
@@ -0,0 +1,351 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::compilation::errors::EvaluationError;
+use crate::compilation::evaluation::Evaluation;
+use crate::compilation::routine::NativeInstruction;
+
+pub const COLLECTION_FEATURES: [(&str, NativeInstruction); 10] = [
+    ("flatten", flatten),
+    ("reverse", reverse),
+    ("sort", sort),
+    ("filter", filter),
+    ("map", map),
+    ("fold", fold),
+    ("contains", contains),
+    ("distinct", distinct),
+    ("zip", zip),
+    ("range", range),
+];
+
+// The most numbers a range may hold, so a mistyped end does not exhaust the memory
+const MAX_RANGE: i64 = 1_000_000;
+// Beyond this a number cannot tell apart neighbouring whole numbers
+const EXACT_LIMIT: f64 = (1u32 << f32::MANTISSA_DIGITS) as f64;
+
+// Only one level of nesting is removed per call, a non-collective subject is an error
+fn flatten(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+
+    let flattened = elements.iter()
+        .flat_map(|element| match element {
+            Evaluation::Collective(inner) => inner.to_vec(),
+            scalar => vec![scalar.clone()],
+        })
+        .collect::<Vec<_>>();
+    Ok(Evaluation::Collective(flattened.into()))
+}
+
+// Text is reversed by character, so multi-byte characters stay intact
+fn reverse(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    match subject {
+        Evaluation::Collective(elements) => Ok(Evaluation::Collective(elements.iter().rev().cloned().collect())),
+        Evaluation::Text(text) => Ok(Evaluation::Text(text.chars().rev().collect::<String>().as_str().into())),
+        other => Err(EvaluationError::new(&format!("Expected a collective or text subject but found {other}"))),
+    }
+}
+
+// The sort is stable, numbers go ascending and text lexicographically
+fn sort(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let mut elements = collective_subject(subject)?.to_vec();
+
+    let all_numbers = elements.iter().all(|element| matches!(element, Evaluation::Number(_)));
+    let all_text = elements.iter().all(|element| matches!(element, Evaluation::Text(_)));
+    if !all_numbers && !all_text {
+        return Err(EvaluationError::new("Expected a collective of only numbers or only text"));
+    }
+
+    if elements.iter().any(|element| matches!(element, Evaluation::Number(value) if value.is_nan())) {
+        return Err(EvaluationError::new("Unable to sort a collective containing an undefined number"));
+    }
+
+    elements.sort_by(|left, right| match (left, right) {
+        (Evaluation::Number(left), Evaluation::Number(right)) => left.partial_cmp(right).unwrap_or(Ordering::Equal),
+        (Evaluation::Text(left), Evaluation::Text(right)) => left.cmp(right),
+        _ => Ordering::Equal,
+    });
+    Ok(Evaluation::Collective(elements.into()))
+}
+
+// Membership is decided by Evaluation::equal, so an element of another type is an error rather than a mismatch
+fn contains(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+    Ok(Evaluation::Boolean(includes(elements, object)?))
+}
+
+// The first of the equal elements is kept, so the order of the collective is preserved
+fn distinct(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+
+    // Only the elements sharing a hash are compared, so elements of different types are simply distinct
+    let mut buckets: HashMap<u64, Vec<Evaluation>> = HashMap::new();
+    let mut kept = Vec::new();
+    for element in elements.iter() {
+        let mut hasher = DefaultHasher::new();
+        element.hash(&mut hasher);
+
+        let bucket = buckets.entry(hasher.finish()).or_default();
+        if !includes(bucket, element)? {
+            bucket.push(element.clone());
+            kept.push(element.clone());
+        }
+    }
+    Ok(Evaluation::Collective(kept.into()))
+}
+
+// Pairs are cut to the shorter side, a single value counts as a collective of one and void as empty
+fn zip(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let pairs = as_elements(subject).iter()
+        .zip(as_elements(object).iter())
+        .map(|(left, right)| Evaluation::Collective([left.clone(), right.clone()].into()))
+        .collect::<Vec<_>>();
+    Ok(Evaluation::Collective(pairs.into()))
+}
+
+// Counts up from the start to before the end, a lone number is the end of a range starting at zero
+fn range(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let (start, end) = match subject {
+        Evaluation::Number(end) => (0.0, *end),
+        Evaluation::Collective(bounds) => match bounds.as_ref() {
+            [Evaluation::Number(start), Evaluation::Number(end)] => (*start, *end),
+            _ => return Err(EvaluationError::new("Expected a start and an end as numbers")),
+        },
+        other => return Err(EvaluationError::new(&format!("Expected an end or a start and an end as numbers but found {other}"))),
+    };
+
+    // The bounds are checked in double precision, so a huge bound is neither rounded nor saturated first
+    let (start, end) = (f64::from(start), f64::from(end));
+    for (name, bound) in [("start", start), ("end", end)] {
+        if bound.fract() != 0.0 || !bound.is_finite() {
+            return Err(EvaluationError::new(&format!("Expected the {name} to be a whole number but found {bound}")));
+        }
+        if bound.abs() > EXACT_LIMIT {
+            return Err(EvaluationError::new(&format!("Expected the {name} to be at most {EXACT_LIMIT} in size but found {bound}")));
+        }
+    }
+
+    if end - start > MAX_RANGE as f64 {
+        return Err(EvaluationError::new(&format!("Expected a range of at most {MAX_RANGE} numbers but found {}", end - start)));
+    }
+
+    let numbers = (start as i64..end as i64)
+        .map(|number| Evaluation::Number(number as f32))
+        .collect::<Vec<_>>();
+    Ok(Evaluation::Collective(numbers.into()))
+}
+
+fn as_elements(value: &Evaluation) -> &[Evaluation] {
+    match value {
+        Evaluation::Collective(elements) => elements,
+        Evaluation::Void => &[],
+        single => std::slice::from_ref(single),
+    }
+}
+
+fn includes(elements: &[Evaluation], value: &Evaluation) -> Result<bool, EvaluationError> {
+    for (index, element) in elements.iter().enumerate() {
+        let equal = element.equal(value)
+            .map_err(|error| EvaluationError::new(&format!("In collective element {}", index + 1)).concat(error))?;
+        if equal {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// The predicate verb is performed on each element with no object and has to give a notion
+fn filter(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+    let predicate = match object {
+        Evaluation::Action(routine) => routine,
+        other => return Err(EvaluationError::new(&format!("Expected a verb as the predicate but found {other}"))),
+    };
+
+    let mut kept = Vec::new();
+    for element in elements {
+        match predicate.execute(element, &Evaluation::Void)? {
+            Evaluation::Boolean(true) => kept.push(element.clone()),
+            Evaluation::Boolean(false) => {},
+            other => return Err(EvaluationError::new(&format!("Expected the predicate to give a notion but found {other}"))),
+        }
+    }
+    Ok(Evaluation::Collective(kept.into()))
+}
+
+// Every element has to give a value, so the result has as many elements as the subject
+fn map(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+    let transform = match object {
+        Evaluation::Action(routine) => routine,
+        other => return Err(EvaluationError::new(&format!("Expected a verb to map with but found {other}"))),
+    };
+
+    let mut mapped = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        match transform.execute(element, &Evaluation::Void)? {
+            Evaluation::Void => {
+                let msg = format!("Expected a value from {transform} for collective element {} but found void", index + 1);
+                return Err(EvaluationError::new(&msg));
+            },
+            value => mapped.push(value),
+        }
+    }
+    Ok(Evaluation::Collective(mapped.into()))
+}
+
+// Reduces left to right with the accumulator as the subject and each element as the object
+fn fold(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let elements = collective_subject(subject)?;
+    let (initial, step) = match object {
+        Evaluation::Collective(arguments) => match arguments.as_ref() {
+            [initial, Evaluation::Action(routine)] => (initial, routine),
+            _ => return Err(EvaluationError::new("Expected an initial value and a verb")),
+        },
+        _ => return Err(EvaluationError::new("Expected an initial value and a verb")),
+    };
+
+    elements.iter().try_fold(initial.clone(), |accumulator, element| step.execute(&accumulator, element))
+}
+
+fn collective_subject(subject: &Evaluation) -> Result<&[Evaluation], EvaluationError> {
+    match subject {
+        Evaluation::Collective(elements) => Ok(elements),
+        other => Err(EvaluationError::new(&format!("Expected a collective subject but found {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::intepreter::run_source;
+    use crate::compilation::routine::Routine;
+
+    fn positive(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+        match subject {
+            Evaluation::Number(number) => Ok(Evaluation::Boolean(*number > 0.0)),
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn numbers(values: &[f32]) -> Evaluation {
+        Evaluation::Collective(values.iter().map(|value| Evaluation::Number(*value)).collect())
+    }
+
+    #[test]
+    fn flatten_removes_one_level() {
+        let nested = Evaluation::Collective([numbers(&[3.0])].into());
+        assert_eq!(run_source("(([1], [2]), [3]) flatten."), Ok(numbers(&[1.0, 2.0, 3.0])));
+        assert_eq!(flatten(&Evaluation::Collective([numbers(&[1.0]), nested].into()), &Evaluation::Void), Ok(Evaluation::Collective([Evaluation::Number(1.0), numbers(&[3.0])].into())));
+        assert!(run_source("[1] flatten.").is_err());
+    }
+
+    #[test]
+    fn reverse_keeps_characters_whole() {
+        assert_eq!(run_source("([1], [2], [3]) reverse."), Ok(numbers(&[3.0, 2.0, 1.0])));
+        assert_eq!(run_source("\"héllo\" reverse."), Ok(Evaluation::Text("olléh".into())));
+        assert!(run_source("[1] reverse.").is_err());
+    }
+
+    #[test]
+    fn sort_orders_numbers_or_text() {
+        assert_eq!(run_source("([3], [1], [2]) sort."), Ok(numbers(&[1.0, 2.0, 3.0])));
+        assert_eq!(run_source("(\"pear\", \"apple\") sort."), Ok(Evaluation::Collective([Evaluation::Text("apple".into()), Evaluation::Text("pear".into())].into())));
+        assert!(run_source("([1], \"a\") sort.").is_err());
+        assert!(sort(&numbers(&[1.0, f32::NAN]), &Evaluation::Void).is_err());
+    }
+
+    #[test]
+    fn filter_keeps_what_the_predicate_holds_for() {
+        let predicate = Evaluation::Action(Routine::new_native("positive", positive));
+        assert_eq!(filter(&numbers(&[-1.0, 2.0, 0.0, 3.0]), &predicate), Ok(numbers(&[2.0, 3.0])));
+
+        let error = filter(&Evaluation::Collective([Evaluation::Text("a".into())].into()), &predicate).expect_err("The predicate gives text");
+        assert!(error.to_string().contains("notion"));
+        assert!(run_source("([1], [2]) filter [1].").is_err());
+    }
+
+    #[test]
+    fn map_transforms_every_element() {
+        let texts = |texts: &[&str]| Ok(Evaluation::Collective(texts.iter().map(|text| Evaluation::Text((*text).into())).collect()));
+        assert_eq!(run_source("((\"a\", \"b\") map uppercase)."), texts(&["A", "B"]));
+        assert!(run_source("(([1], [2]) map uppercase).").is_err());
+
+        let nothing = Evaluation::Action(Routine::new_native("nothing", |_, _| Ok(Evaluation::Void)));
+        let error = map(&numbers(&[1.0]), &nothing).expect_err("Every element has to give a value");
+        assert!(error.to_string().contains("void"));
+    }
+
+    #[test]
+    fn fold_reduces_left_to_right() {
+        let subtract = Evaluation::Action(Routine::new_native("subtract", |accumulator, element| match (accumulator, element) {
+            (Evaluation::Number(accumulator), Evaluation::Number(element)) => Ok(Evaluation::Number(accumulator - element)),
+            _ => Err(EvaluationError::new("Expected numbers")),
+        }));
+        let arguments = Evaluation::Collective([Evaluation::Number(10.0), subtract].into());
+        assert_eq!(fold(&numbers(&[1.0, 2.0, 3.0]), &arguments), Ok(Evaluation::Number(4.0)));
+        assert_eq!(fold(&numbers(&[]), &arguments), Ok(Evaluation::Number(10.0)));
+        assert!(run_source("(([1], [2]) fold [0]).").is_err());
+    }
+
+    #[test]
+    fn zip_pairs_up_to_the_shorter_side() {
+        let pair = |left: f32, right: &str| Evaluation::Collective([Evaluation::Number(left), Evaluation::Text(right.into())].into());
+        assert_eq!(run_source("(([1], [2]) zip (\"a\", \"b\", \"c\"))."), Ok(Evaluation::Collective([pair(1.0, "a"), pair(2.0, "b")].into())));
+        assert_eq!(run_source("([1] zip \"a\")."), Ok(Evaluation::Collective([pair(1.0, "a")].into())));
+        assert_eq!(zip(&numbers(&[1.0]), &Evaluation::Void), Ok(numbers(&[])));
+    }
+
+    #[test]
+    fn range_counts_up_to_the_end() {
+        assert_eq!(range(&Evaluation::Number(3.0), &Evaluation::Void), Ok(numbers(&[0.0, 1.0, 2.0])));
+        assert_eq!(range(&numbers(&[2.0, 4.0]), &Evaluation::Void), Ok(numbers(&[2.0, 3.0])));
+        assert_eq!(run_source("[3] range."), Ok(numbers(&[0.0, 1.0, 2.0])));
+        assert_eq!(run_source("([4], [2]) range."), Ok(numbers(&[])));
+        assert!(run_source("[2.5] range.").is_err());
+    }
+
+    #[test]
+    fn range_is_capped() {
+        let too_many = MAX_RANGE as f32 + 1.0;
+        assert!(range(&Evaluation::Number(too_many), &Evaluation::Void).is_err());
+        assert!(range(&numbers(&[-too_many, 1.0]), &Evaluation::Void).is_err());
+    }
+
+    #[test]
+    fn contains_compares_nouns_by_value() {
+        // Noun instances carry no fields at runtime yet, so two fields are kept beside them in a pair
+        let point = |x: f32, y: f32| Evaluation::Collective([Evaluation::Custom("point".into()), numbers(&[x, y])].into());
+        let points = Evaluation::Collective([point(1.0, 2.0), point(3.0, 4.0)].into());
+
+        assert_eq!(contains(&points, &point(3.0, 4.0)), Ok(Evaluation::Boolean(true)));
+        assert_eq!(contains(&points, &point(4.0, 3.0)), Ok(Evaluation::Boolean(false)));
+
+        let nouns = Evaluation::Collective([Evaluation::Custom("point".into())].into());
+        assert_eq!(contains(&nouns, &Evaluation::Custom(String::from("point").into())), Ok(Evaluation::Boolean(true)));
+        let error = contains(&nouns, &Evaluation::Custom("line".into())).expect_err("Different nouns cannot be compared");
+        assert!(error.to_string().contains("In collective element 1"), "{error}");
+    }
+
+    #[test]
+    fn distinct_keeps_the_first_of_equal_elements() {
+        assert_eq!(run_source("([3], [1], [3], [2], [1]) distinct."), Ok(numbers(&[3.0, 1.0, 2.0])));
+        assert_eq!(run_source("([1], [2], [3]) distinct."), Ok(numbers(&[1.0, 2.0, 3.0])));
+        assert_eq!(distinct(&numbers(&[0.0, -0.0]), &Evaluation::Void), Ok(numbers(&[0.0])));
+        assert!(run_source("[1] distinct.").is_err());
+    }
+
+    #[test]
+    fn range_is_checked_near_its_bounds() {
+        let largest = range(&Evaluation::Number(MAX_RANGE as f32), &Evaluation::Void);
+        assert!(matches!(largest, Ok(Evaluation::Collective(numbers)) if numbers.len() == MAX_RANGE as usize));
+        assert!(range(&Evaluation::Number(MAX_RANGE as f32 + 1.0), &Evaluation::Void).is_err());
+
+        let exact = EXACT_LIMIT as f32;
+        assert_eq!(range(&numbers(&[exact - 2.0, exact]), &Evaluation::Void), Ok(numbers(&[exact - 2.0, exact - 1.0])));
+        let error = range(&numbers(&[exact * 2.0, exact * 2.0 + 4.0]), &Evaluation::Void).expect_err("The start is not exact");
+        assert!(error.to_string().contains("start"), "{error}");
+        assert!(range(&numbers(&[f32::MIN, 0.0]), &Evaluation::Void).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+use crate::compilation::environment::{Environment, Variable};
+use crate::compilation::evaluation::Evaluation;
+use crate::compilation::routine::{NativeInstruction, Routine};
+
+mod collection;
+mod testing;
+mod text;
+mod time;
+
+// Every builtin verb is listed here, so registering them and checking for shadowing agree
+const FEATURES: [&[(&str, NativeInstruction)]; 4] = [
+    &collection::COLLECTION_FEATURES,
+    &testing::TESTING_FEATURES,
+    &text::TEXT_FEATURES,
+    &time::TIME_FEATURES,
+];
+
+pub fn add_builtin_features(environment: &mut Environment) {
+    for &(name, instruction) in FEATURES.iter().copied().flatten() {
+        add_native(environment, name, instruction);
+    }
+}
+
+pub fn is_builtin(name: &str) -> bool {
+    FEATURES.iter().copied().flatten().any(|&(builtin, _)| builtin == name)
+}
+
+fn add_native(environment: &mut Environment, name: &str, instruction: NativeInstruction) {
+    let variable = Variable { fixed: true, ..Variable::with(name) };
+    environment.define(variable, Evaluation::Action(Routine::new_native(name, instruction)))
+        .expect("Builtin names are unique");
+}
@@ -0,0 +1,63 @@
+use crate::compilation::errors::EvaluationError;
+use crate::compilation::evaluation::Evaluation;
+use crate::compilation::routine::NativeInstruction;
+
+pub const TESTING_FEATURES: [(&str, NativeInstruction); 2] = [
+    ("assert", assert),
+    ("assert_equal", assert_equal),
+];
+
+// The notion is the subject, e.g. `total = [3] assert "the total is wrong".`
+fn assert(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let holds = match subject {
+        Evaluation::Boolean(holds) => *holds,
+        other => return Err(EvaluationError::new(&format!("Expected a notion to assert but found {other}"))),
+    };
+
+    if holds {
+        return Ok(Evaluation::Void);
+    }
+
+    match object {
+        Evaluation::Void => Err(EvaluationError::new("Assertion failed")),
+        Evaluation::Text(message) => Err(EvaluationError::new(&format!("Assertion failed: {message}"))),
+        other => Err(EvaluationError::new(&format!("Expected a text message for the assertion but found {other}"))),
+    }
+}
+
+// The actual value is the subject and the expected value the object, e.g. `total assert_equal [3].`
+fn assert_equal(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    if subject.equal(object)? {
+        Ok(Evaluation::Void)
+    } else {
+        Err(EvaluationError::new(&format!("Assertion failed: expected {object} but found {subject}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::evaluation::Evaluation;
+    use crate::compilation::intepreter::run_source;
+
+    #[test]
+    fn assert_fails_with_its_message() {
+        assert_eq!(run_source("true assert."), Ok(Evaluation::Void));
+        assert_eq!(run_source("([1] = [1]) assert \"one is one\"."), Ok(Evaluation::Void));
+
+        let error = run_source("false assert \"the total is wrong\".").expect_err("A false notion fails");
+        assert!(error.to_string().contains("Assertion failed: the total is wrong"), "{error}");
+        let error = run_source("false assert.").expect_err("A false notion fails");
+        assert!(error.to_string().contains("Assertion failed"), "{error}");
+        assert!(run_source("[1] assert.").is_err());
+    }
+
+    #[test]
+    fn assert_equal_names_both_sides() {
+        assert_eq!(run_source("([1] + [2]) assert_equal [3]."), Ok(Evaluation::Void));
+        assert_eq!(run_source("(\"a\", [1]) assert_equal (\"a\", [1])."), Ok(Evaluation::Void));
+
+        let error = run_source("([1] + [1]) assert_equal [3].").expect_err("Two is not three").to_string();
+        assert!(error.contains("expected 3 but found 2"), "{error}");
+        assert!(run_source("[1] assert_equal \"1\".").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::compilation::errors::EvaluationError;
+use crate::compilation::evaluation::Evaluation;
+use crate::compilation::routine::NativeInstruction;
+
+pub const TEXT_FEATURES: [(&str, NativeInstruction); 9] = [
+    ("substring", substring),
+    ("uppercase", uppercase),
+    ("lowercase", lowercase),
+    ("trim", trim),
+    ("trim_start", trim_start),
+    ("trim_end", trim_end),
+    ("split", split),
+    ("join", join),
+    ("format", format),
+];
+
+// The most decimals a number may be formatted with, far beyond what a number holds
+const MAX_PRECISION: usize = 32;
+
+// Indices count characters rather than bytes, the length is clamped to the characters available
+fn substring(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let text = text_subject(subject)?;
+
+    let (start, length) = match object {
+        Evaluation::Collective(bounds) => match bounds.as_ref() {
+            [Evaluation::Number(start), Evaluation::Number(length)] => (*start, *length),
+            _ => return Err(EvaluationError::new("Expected a start and a length as numbers")),
+        },
+        _ => return Err(EvaluationError::new("Expected a start and a length as numbers")),
+    };
+
+    let start = whole_number(start, "start")?;
+    let length = whole_number(length, "length")?;
+
+    let slice = text.chars().skip(start).take(length).collect::<String>();
+    Ok(Evaluation::Text(slice.as_str().into()))
+}
+
+// Case mapping is Unicode-aware and may change the length, e.g. "straße" becomes "STRASSE"
+fn uppercase(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Ok(Evaluation::Text(text_subject(subject)?.to_uppercase().as_str().into()))
+}
+
+fn lowercase(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Ok(Evaluation::Text(text_subject(subject)?.to_lowercase().as_str().into()))
+}
+
+fn trim(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Ok(Evaluation::Text(text_subject(subject)?.trim().into()))
+}
+
+fn trim_start(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Ok(Evaluation::Text(text_subject(subject)?.trim_start().into()))
+}
+
+fn trim_end(subject: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Ok(Evaluation::Text(text_subject(subject)?.trim_end().into()))
+}
+
+// An empty subject splits into a single empty text
+fn split(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let text = text_subject(subject)?;
+    let delimiter = match object {
+        Evaluation::Text(delimiter) if !delimiter.is_empty() => delimiter,
+        Evaluation::Text(_) => return Err(EvaluationError::new("Unable to split by an empty delimiter")),
+        other => return Err(EvaluationError::new(&format!("Expected a text delimiter but found {other}"))),
+    };
+
+    let pieces = text.split(delimiter.as_ref())
+        .map(|piece| Evaluation::Text(piece.into()))
+        .collect::<Vec<_>>();
+    Ok(Evaluation::Collective(pieces.into()))
+}
+
+// A single text subject is joined as a collective of one
+fn join(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let pieces = match subject {
+        Evaluation::Collective(pieces) => pieces.as_ref(),
+        Evaluation::Text(_) => std::slice::from_ref(subject),
+        other => return Err(EvaluationError::new(&format!("Expected a collective of text but found {other}"))),
+    };
+    let separator = match object {
+        Evaluation::Text(separator) => separator,
+        other => return Err(EvaluationError::new(&format!("Expected a text separator but found {other}"))),
+    };
+
+    let pieces = pieces.iter()
+        .enumerate()
+        .map(|(index, piece)| match piece {
+            Evaluation::Text(text) => Ok(text.as_ref()),
+            other => Err(EvaluationError::new(&format!("Expected text in collective element {} but found {other}", index + 1))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Evaluation::Text(pieces.join(separator).as_str().into()))
+}
+
+// Without a precision any value is written as it would be displayed, e.g. [3.14159] format [2] is "3.14"
+fn format(subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    let formatted = match (subject, object) {
+        (subject, Evaluation::Void) => subject.to_string(),
+        (Evaluation::Number(value), Evaluation::Number(precision)) => {
+            let precision = whole_number(*precision, "precision")?;
+            if precision > MAX_PRECISION {
+                return Err(EvaluationError::new(&format!("Expected a precision of at most {MAX_PRECISION} but found {precision}")));
+            }
+            format!("{value:.precision$}")
+        },
+        (Evaluation::Number(_), other) => return Err(EvaluationError::new(&format!("Expected the precision as a number but found {other}"))),
+        (other, _) => return Err(EvaluationError::new(&format!("Expected a number subject to format with a precision but found {other}"))),
+    };
+
+    Ok(Evaluation::Text(formatted.as_str().into()))
+}
+
+fn text_subject(subject: &Evaluation) -> Result<&str, EvaluationError> {
+    match subject {
+        Evaluation::Text(text) => Ok(text),
+        other => Err(EvaluationError::new(&format!("Expected a text subject but found {other}"))),
+    }
+}
+
+fn whole_number(value: f32, name: &str) -> Result<usize, EvaluationError> {
+    if value < 0.0 || value.fract() != 0.0 {
+        return Err(EvaluationError::new(&format!("Expected the {name} to be a whole number but found {value}")));
+    }
+
+    Ok(value as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::intepreter::run_source;
+
+    fn text(value: &str) -> Result<Evaluation, EvaluationError> {
+        Ok(Evaluation::Text(value.into()))
+    }
+
+    #[test]
+    fn substring_counts_characters() {
+        assert_eq!(run_source("(\"héllo wörld\" substring ([1], [4]))."), text("éllo"));
+        assert_eq!(run_source("(\"wörld\" substring ([3], [10]))."), text("ld"));
+        assert!(run_source("(\"wörld\" substring (-[1], [2])).").is_err());
+    }
+
+    #[test]
+    fn case_mapping_is_unicode_aware() {
+        assert_eq!(run_source("\"straße\" uppercase."), text("STRASSE"));
+        assert_eq!(run_source("\"ÉCOLE\" lowercase."), text("école"));
+        assert!(run_source("[1] uppercase.").is_err());
+    }
+
+    #[test]
+    fn trim_removes_whitespace() {
+        assert_eq!(run_source("\"  tea \t\" trim."), text("tea"));
+        assert_eq!(run_source("\"  tea  \" trim_start."), text("tea  "));
+        assert_eq!(run_source("\"  tea  \" trim_end."), text("  tea"));
+    }
+
+    #[test]
+    fn split_gives_a_collective() {
+        let pieces = |pieces: &[&str]| Ok(Evaluation::Collective(pieces.iter().map(|piece| Evaluation::Text((*piece).into())).collect()));
+        assert_eq!(run_source("(\"a,b,,c\" split \",\")."), pieces(&["a", "b", "", "c"]));
+        assert_eq!(run_source("(\"\" split \",\")."), pieces(&[""]));
+        assert!(run_source("(\"a,b\" split \"\").").is_err());
+    }
+
+    #[test]
+    fn join_puts_the_separator_between() {
+        assert_eq!(run_source("((\"a\", \"b\", \"c\") join \", \")."), text("a, b, c"));
+        assert_eq!(run_source("(\"a\" join \"-\")."), text("a"));
+        assert!(run_source("((\"a\", [1]) join \"-\").").is_err());
+    }
+
+    #[test]
+    fn format_rounds_to_the_precision() {
+        assert_eq!(format(&Evaluation::Number(1.23456), &Evaluation::Number(2.0)), Ok(Evaluation::Text("1.23".into())));
+        assert_eq!(format(&Evaluation::Number(2.5), &Evaluation::Void), Ok(Evaluation::Text("2.5".into())));
+    }
+
+    #[test]
+    fn format_precision_is_capped() {
+        let precision = Evaluation::Number(MAX_PRECISION as f32 + 1.0);
+        assert!(format(&Evaluation::Number(1.0), &precision).is_err());
+        assert!(format(&Evaluation::Number(1.0), &Evaluation::Number(1e30)).is_err());
+    }
+}
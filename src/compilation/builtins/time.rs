@@ -0,0 +1,41 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::compilation::errors::EvaluationError;
+use crate::compilation::evaluation::Evaluation;
+use crate::compilation::routine::NativeInstruction;
+
+pub const TIME_FEATURES: [(&str, NativeInstruction); 1] = [
+    ("now", now),
+];
+
+// Numbers are single precision, which cannot tell apart seconds since the epoch,
+// so the clock counts the seconds since it was first read instead
+fn now(_: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    static START: OnceLock<Instant> = OnceLock::new();
+    let start = START.get_or_init(Instant::now);
+    Ok(Evaluation::Number(start.elapsed().as_secs_f32()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    fn seconds() -> f32 {
+        match now(&Evaluation::Void, &Evaluation::Void) {
+            Ok(Evaluation::Number(seconds)) => seconds,
+            other => panic!("Expected a number but found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn now_counts_up_from_the_first_read() {
+        let first = seconds();
+        assert!(first >= 0.0);
+
+        thread::sleep(Duration::from_millis(20));
+        assert!(seconds() > first);
+    }
+}
@@ -1,18 +1,26 @@
 use std::rc::Rc;
 use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::UNIX_EPOCH;
 
 use walkdir::WalkDir;
 
 use crate::projects::project::Project;
+use crate::compilation::analysis::{analyse, validate};
+use crate::compilation::environment::Environment;
+use crate::compilation::evaluation::Evaluation;
 use crate::compilation::intepreter::Intepreter;
 use crate::compilation::intermediate::Intermediate;
+use crate::compilation::ir::{self, Ir};
 use crate::compilation::source::Source;
-use crate::compilation::scanner::{self, Scanner};
-use crate::compilation::token::Token;
+use crate::compilation::scanner::{self, Options, Scanner};
+use crate::compilation::token::{Token, TokenType};
 use crate::compilation::parser::Parser;
-use crate::compilation::statement::Statement;
+use crate::compilation::statement::{Program, Statement};
 use crate::compilation::errors::CompilerError;
-use crate::utils::hasher::hash_file;
+use crate::compilation::typecheck;
+use crate::utils::hasher::hash_bytes;
 
 pub trait CompilerState {}
 
@@ -25,17 +33,35 @@ pub struct Initial;
 #[derive(Default)]
 pub struct Ready {
     pub sources : Rc<[Source]>,
+    pub jobs: usize,
+    pub bare_numbers: bool,
+    pub warnings: Rc<[Rc<str>]>,
 }
 #[derive(Default)]
 pub struct Tokenized {
     pub tokens : Rc<[Token]>,
+    pub strict: bool,
 }
 #[derive(Default)]
 pub struct Parsed {
     pub statements : Rc<[Statement]>,
+    pub trace: bool,
 }
+// What a clean removed from the intermediate directory
+#[derive(Default, Debug)]
+pub struct Cleaned {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+// Beyond this size the intermediate directory is only removed when forced
+const CLEAN_LIMIT: u64 = 64 * 1024 * 1024;
+
 #[derive(Default)]
-pub struct Evaluated;
+pub struct Evaluated {
+    values : Rc<[Evaluation]>,
+    environment: Environment,
+}
 
 impl CompilerState for Initial {}
 impl CompilerState for Ready {}
@@ -44,10 +70,11 @@ impl CompilerState for Parsed {}
 impl CompilerState for Evaluated {}
 
 impl Compiler<Initial> {
-    pub fn new() -> Result<Compiler<Ready>, CompilerError> {
-        let source_directory = Project::get_source_dir(false)?;
+    pub fn new(root: &Path) -> Result<Compiler<Ready>, CompilerError> {
+        let source_directory = Project::get_source_dir(root, false)?;
 
         let mut sources = Vec::new();
+        let mut skipped = Vec::new();
         for entry in WalkDir::new(source_directory.as_path()) {
             let entry = entry?;
             if entry.file_type().is_dir() {
@@ -62,82 +89,292 @@ impl Compiler<Initial> {
             let filename = entry.file_name().to_string_lossy();
 
             if filename.ends_with(".prs") {
-                let hash = hash_file(full_path)?;
-                let source = Source::new(path.as_ref(), filename.as_ref(), hash.as_slice())?;
+                // Only the modified time is read here, the file is hashed later if the cache is stale
+                let modified = entry.metadata()?.modified()?
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|e| CompilerError::SourceError(e.to_string().as_str().into()))?
+                    .as_nanos();
+                let source = Source::new(root, path.as_ref(), filename.as_ref(), modified)?;
                 sources.push(source);
+            } else {
+                skipped.push(path.to_string());
             }
         }
-        
+
+        // A misnamed source would otherwise just be missing from the build
+        let mut warnings = Vec::new();
+        if !skipped.is_empty() {
+            warnings.push(format!("Skipped {}, only .prs files are compiled", skipped.join(", ")).into());
+        }
+        if sources.is_empty() {
+            let msg = format!("No .prs sources found in {}", source_directory.display());
+            return Err(CompilerError::SourceError(msg.as_str().into()));
+        }
+
         Ok(Compiler {
-            state: Ready { sources: sources.into() }
+            state: Ready { sources: sources.into(), jobs: 1, bare_numbers: false, warnings: warnings.into() }
         })
     }
 
-    pub fn clean() -> Result<Compiler<Initial>, CompilerError> {
-        if let Ok(intermediate_path) = Project::get_intermediate_dir(false) {
-            fs::remove_dir_all(intermediate_path)?;
+    // Files other than cached tokens, or an unexpectedly large directory, are only removed when forced
+    pub fn clean(root: &Path, force: bool) -> Result<Cleaned, CompilerError> {
+        let intermediate_path = match Project::get_intermediate_dir(root, false) {
+            Ok(path) => path,
+            Err(_) => return Ok(Cleaned::default()),
+        };
+
+        let mut cleaned = Cleaned::default();
+        let mut foreign = Vec::new();
+        for entry in WalkDir::new(intermediate_path.as_path()) {
+            let entry = entry?;
+            if entry.file_type().is_dir() {
+                continue;
+            }
+
+            cleaned.files += 1;
+            cleaned.bytes += entry.metadata()?.len();
+            if entry.path().extension().is_none_or(|extension| extension != "prt") {
+                foreign.push(entry.path().display().to_string());
+            }
+        }
+
+        if !force && !foreign.is_empty() {
+            let msg = format!("The intermediate directory also holds {}, use --force to remove it anyway", foreign.join(", "));
+            return Err(CompilerError::SourceError(msg.as_str().into()));
+        }
+        if !force && cleaned.bytes > CLEAN_LIMIT {
+            let msg = format!("The intermediate directory holds {} bytes, use --force to remove it anyway", cleaned.bytes);
+            return Err(CompilerError::SourceError(msg.as_str().into()));
         }
 
-        Ok(Compiler { state: Initial })
+        fs::remove_dir_all(intermediate_path)?;
+        Ok(cleaned)
     }
 }
 
 impl Compiler<Ready> {
+    // The files found beside the sources that are left out of the build
+    pub fn warnings(&self) -> &[Rc<str>] {
+        &self.state.warnings
+    }
+
+    // The number of threads the sources are tokenized on
+    pub fn jobs(self, jobs: usize) -> Self {
+        Compiler {
+            state: Ready { jobs: jobs.max(1), ..self.state }
+        }
+    }
+
+    // Whether numbers may also be written without brackets, as in `5` and `3.14`
+    pub fn bare_numbers(self, allow: bool) -> Self {
+        Compiler {
+            state: Ready { bare_numbers: allow, ..self.state }
+        }
+    }
+
     pub fn tokenize(self) -> Result<Compiler<Tokenized>, CompilerError> {
-        let scanners = self.state.sources.iter()
-            .map(to_token)
-            .collect::<Result<Vec<_>, CompilerError>>()?;
+        // Comments are kept in the intermediates, so the docs of definitions survive the cache
+        let options = Options { comments: true, bare_numbers: self.state.bare_numbers };
+        let files = if self.state.jobs > 1 && self.state.sources.len() > 1 {
+            tokenize_parallel(&self.state.sources, self.state.jobs, options)?
+        } else {
+            self.state.sources.iter()
+                .map(|source| to_token(source, options).map(|scanner| scanner.intermediate().tokens.clone()))
+                .collect::<Result<Vec<_>, CompilerError>>()?
+        };
 
-        let tokens = scanners.into_iter()
-            .map(|scanner| scanner.intermediate().tokens.to_vec())
-            .flatten()
-            .collect::<Vec<_>>();
+        Ok(Compiler {
+            state: Tokenized { tokens: join_files(&files), strict: false }
+        })
+    }
 
-        /*for token in &tokens {
-            println!("{token}");
-        }*/
+    // The sources are scanned in memory, so the intermediate files are neither read nor written
+    pub fn scan(self) -> Result<Compiler<Tokenized>, CompilerError> {
+        let options = Options { comments: true, bare_numbers: self.state.bare_numbers };
+        let files = self.state.sources.iter()
+            .map(|source| {
+                let content = source.content()?;
+                let hash = hash_bytes(content.as_bytes());
+                Ok(Scanner::new(content.as_ref(), hash.into()).options(options).tokenize().tokens().into())
+            })
+            .collect::<Result<Vec<_>, CompilerError>>()?;
 
         Ok(Compiler {
-            state: Tokenized { tokens: tokens.into() }
+            state: Tokenized { tokens: join_files(&files), strict: false }
         })
     }
 }
 
 impl Compiler<Tokenized> {
-    pub fn parse(self) -> Result<Compiler<Parsed>, CompilerError> {
+    // In strict mode every warning of the analysis fails the parse
+    pub fn strict(self, strict: bool) -> Self {
+        Compiler {
+            state: Tokenized { strict, ..self.state }
+        }
+    }
+
+    pub fn parse(self, max_errors: usize) -> Result<Compiler<Parsed>, CompilerError> {
         let parser = Parser::new(self.state.tokens)
+            .max_errors(max_errors)
             .parse()?;
+        validate(parser.statements().as_ref())?;
+
+        let warnings = analyse(parser.statements().as_ref());
+        if self.state.strict && !warnings.is_empty() {
+            let error = warnings.iter().fold(CompilerError::None, |error, warning| {
+                error.add(CompilerError::PromotedWarning(warning.clone()))
+            });
+            return Err(error);
+        }
+
+        for warning in warnings {
+            eprintln!("Warning: {}", warning);
+        }
         
         /*for statement in parser.statements().as_ref() {
             println!("{statement}");
         }*/
     
         Ok(Compiler {
-            state: Parsed { statements: parser.statements() }
+            state: Parsed { statements: parser.statements(), trace: false }
         })
     }
 }
 
 impl Compiler<Parsed> {
+    pub fn program(&self) -> Program {
+        Program(self.state.statements.clone())
+    }
+
+    pub fn lower(&self) -> Rc<[Ir]> {
+        ir::lower(&self.state.statements).into()
+    }
+
+    // Reports the type errors that can be found without evaluating, so nothing is run
+    pub fn check(&self) -> Result<(), CompilerError> {
+        typecheck::check(&self.state.statements)
+    }
+
+    pub fn trace(self, trace: bool) -> Self {
+        Compiler {
+            state: Parsed { trace, ..self.state }
+        }
+    }
+
     pub fn evaluate(self) -> Result<Compiler<Evaluated>, CompilerError> {
-        let mut intepreter = Intepreter::new();
+        let mut intepreter = Intepreter::new(self.state.trace);
+        let mut values = Vec::new();
+
+        // Definitions are registered first, so a sentence may use a verb defined further down or in any other file
+        let (definitions, sentences): (Vec<_>, Vec<_>) = self.state.statements.iter()
+            .partition(|statement| matches!(statement,
+                Statement::Noun { .. } | Statement::Verb { .. } | Statement::Adjective { .. }));
+
+        for statement in definitions.into_iter().chain(sentences) {
+            let value = intepreter.execute(statement)
+                .map_err(CompilerError::RuntimeError)?;
 
-        for statement in self.state.statements.as_ref() {
-            intepreter.execute(&statement)
-                .map_err(|error| CompilerError::RuntimeError(error))?;
+            // Only bare phrases yield a value, definitions stay silent
+            if matches!(statement, Statement::Phrase(_)) && value != Evaluation::Void {
+                values.push(value);
+            }
         }
         
         Ok(Compiler {
-            state: Evaluated
+            state: Evaluated { values: values.into(), environment: intepreter.environment() }
         })
     }
 }
 
-fn to_token(source: &Source) -> Result<Scanner<scanner::Done>, CompilerError> {
-    let result = match Intermediate::try_from(source) {
+impl Compiler<Evaluated> {
+    pub fn values(&self) -> &[Evaluation] {
+        &self.state.values
+    }
+
+    // The top-level bindings left after the last statement
+    pub fn environment(&self) -> &Environment {
+        &self.state.environment
+    }
+
+    pub fn values_json(&self) -> Result<String, CompilerError> {
+        serde_json::to_string_pretty(&self.state.values)
+            .map_err(|e| CompilerError::SourceError(e.to_string().as_str().into()))
+    }
+}
+
+// Every file ends with its own EOF, only the last one is kept so the parser reads past empty files
+fn join_files(files: &[Rc<[Token]>]) -> Rc<[Token]> {
+    let mut tokens = Vec::new();
+    let mut end = None;
+    for token in files.iter().flat_map(|file| file.iter()) {
+        if token.name == TokenType::EOF {
+            end = Some(token.clone());
+        } else {
+            tokens.push(token.clone());
+        }
+    }
+    tokens.extend(end);
+    tokens.into()
+}
+
+// Sources and tokens are reference counted, so each thread gets owned copies of the paths
+// and hands back the bytes of its intermediates, which are read back in source order
+fn tokenize_parallel(sources: &[Source], jobs: usize, options: Options) -> Result<Vec<Rc<[Token]>>, CompilerError> {
+    let files = sources.iter()
+        .map(|source| (source.root.to_path_buf(), source.path.to_string(), source.filename.to_string(), source.modified))
+        .collect::<Vec<_>>();
+    let chunk_size = files.len().div_ceil(jobs);
+
+    let results = thread::scope(|scope| {
+        let handles = files.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter()
+                .map(|(root, path, filename, modified)| Source::new(root, path, filename, *modified)
+                    .and_then(|source| to_bytes(&source, options))
+                    .map_err(|error| match error {
+                        CompilerError::SourceError(message) => message.to_string(),
+                        error => error.to_string(),
+                    }))
+                .collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect::<Vec<_>>()
+    });
+
+    results.into_iter()
+        .map(|result| {
+            let bytes = result.map_err(|message| CompilerError::SourceError(message.as_str().into()))?;
+            Ok(Intermediate::try_from(bytes.as_slice())?.tokens)
+        })
+        .collect()
+}
+
+fn to_bytes(source: &Source, options: Options) -> Result<Vec<u8>, CompilerError> {
+    match Intermediate::read_for(source, options) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => {
+            let content = source.content()?;
+            let hash = hash_bytes(content.as_bytes());
+            Scanner::new(content.as_ref(), hash.into())
+                .options(options)
+                .tokenize()
+                .intermediate()
+                .save_for(source)
+        },
+    }
+}
+
+fn to_token(source: &Source, options: Options) -> Result<Scanner<scanner::Done>, CompilerError> {
+    let cached = Intermediate::read_for(source, options)
+        .and_then(|bytes| Intermediate::try_from(bytes.as_slice()));
+    let result = match cached {
         Ok(intermediate) => Scanner::from(intermediate),
         Err(_) => {
-            let scanner = Scanner::new(source.content()?.as_ref(), source.hash.clone())
+            let content = source.content()?;
+            let hash = hash_bytes(content.as_bytes());
+            let scanner = Scanner::new(content.as_ref(), hash.into())
+                .options(options)
                 .tokenize();
             scanner
                 .intermediate()
@@ -148,3 +385,232 @@ fn to_token(source: &Source) -> Result<Scanner<scanner::Done>, CompilerError> {
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+    use crate::compilation::parser::DEFAULT_MAX_ERRORS;
+
+    fn project(name: &str, source: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("stitch-compiler-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let source_dir = root.join(Project::SOURCE_DIR);
+        fs::create_dir_all(&source_dir).expect("Failed to create the source directory");
+        fs::write(source_dir.join("main.prs"), source).expect("Failed to write the source");
+        root
+    }
+
+    #[test]
+    fn scan_skips_the_intermediate_files() {
+        let root = project("scan", "[1] + [2].");
+        let compiler = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .expect("The project should evaluate");
+
+        assert_eq!(compiler.values(), &[Evaluation::Number(3.0)]);
+        assert!(Project::get_intermediate_dir(&root, false).is_err());
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn projects_build_concurrently() {
+        let roots = [project("first", "[1] + [1]."), project("second", "[2] * [3].")];
+        let values = thread::scope(|scope| {
+            let handles = roots.iter()
+                .map(|root| scope.spawn(move || Compiler::new(root)
+                    .and_then(Compiler::tokenize)
+                    .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+                    .and_then(Compiler::evaluate)
+                    .map(|compiler| compiler.values().iter().map(|value| value.to_string()).collect::<Vec<_>>())
+                    .map_err(|error| error.to_string())))
+                .collect::<Vec<_>>();
+            handles.into_iter()
+                .map(|handle| handle.join().expect("The build should not panic"))
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(values, [Ok(vec!["2".to_string()]), Ok(vec!["6".to_string()])]);
+        for root in roots {
+            fs::remove_dir_all(&root).expect("Failed to remove the project");
+        }
+    }
+
+    #[test]
+    fn definitions_are_evaluated_first() {
+        let root = project("definitions", "[1] greet. verb greet for number { hence it. }");
+        let error = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .err()
+            .expect("Verb bodies are not evaluated");
+
+        assert!(error.to_string().contains("not evaluated yet"));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn outdated_intermediate_is_rebuilt() {
+        let root = project("outdated", "[1] + [2].");
+        let tokenize = || Compiler::new(&root)
+            .and_then(Compiler::tokenize)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .map(|compiler| compiler.values().to_vec());
+        assert_eq!(tokenize(), Ok(vec![Evaluation::Number(3.0)]));
+
+        let path = Project::get_intermediate_dir(&root, false).expect("The intermediate should exist").join("main.prt");
+        let saved = fs::read(&path).expect("The intermediate should be saved");
+        let current = Intermediate::try_from(saved.as_slice()).expect("The intermediate should load");
+        let outdated = Intermediate { version: "0.0.0+tokens.0".into(), tokens: Rc::from([]), ..current };
+        fs::write(&path, bincode::serialize(&outdated).expect("The intermediate should serialize")).expect("Failed to write the intermediate");
+
+        assert_eq!(tokenize(), Ok(vec![Evaluation::Number(3.0)]));
+        let rebuilt = Intermediate::try_from(fs::read(&path).expect("The intermediate should be saved").as_slice())
+            .expect("The intermediate should load");
+        assert_ne!(rebuilt.version, outdated.version);
+        assert!(!rebuilt.tokens.is_empty());
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn only_bare_phrases_give_values() {
+        let root = project("values", "so x is number as [1]. noun cat { } x as [4]. [2] plus [3].");
+        let compiler = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .expect("The project should evaluate");
+
+        let printed = compiler.values().iter().map(|value| value.to_string()).collect::<Vec<_>>();
+        assert_eq!(printed, ["5"]);
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn empty_source_has_no_statements() {
+        let root = project("empty", "  \n");
+        let compiler = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .expect("An empty source should parse");
+
+        assert!(compiler.program().0.is_empty());
+        assert_eq!(compiler.evaluate().map(|compiler| compiler.values().to_vec()), Ok(vec![]));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn empty_files_do_not_hide_the_others() {
+        let root = project("mixed", "[1] + [2].");
+        let source_dir = root.join(Project::SOURCE_DIR);
+        fs::write(source_dir.join("a.prs"), "").expect("Failed to write the source");
+        fs::write(source_dir.join("z.prs"), "\n").expect("Failed to write the source");
+
+        let values = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .map(|compiler| compiler.values().to_vec());
+        assert_eq!(values, Ok(vec![Evaluation::Number(3.0)]));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn byte_order_mark_is_skipped() {
+        let root = project("bom", "\u{feff}[1] + [2].");
+        let values = Compiler::new(&root)
+            .and_then(Compiler::tokenize)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .map(|compiler| compiler.values().to_vec());
+        assert_eq!(values, Ok(vec![Evaluation::Number(3.0)]));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn invalid_utf8_is_located() {
+        let root = project("utf8", "");
+        fs::write(root.join(Project::SOURCE_DIR).join("main.prs"), b"[1] + \xff[2].").expect("Failed to write the source");
+
+        for tokenize in [Compiler::tokenize, Compiler::scan] {
+            let error = Compiler::new(&root)
+                .and_then(tokenize)
+                .err()
+                .expect("The source is not UTF-8");
+            assert!(matches!(error, CompilerError::SourceError(_)), "{error}");
+            assert!(error.to_string().contains("main.prs is not valid UTF-8 at byte 6"), "{error}");
+        }
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn clean_counts_the_removed_files() {
+        let root = project("clean", "[1] + [2].");
+        fs::write(root.join(Project::SOURCE_DIR).join("other.prs"), "[3].").expect("Failed to write the source");
+        Compiler::new(&root).and_then(Compiler::tokenize).expect("The project should tokenize");
+
+        let intermediate = Project::get_intermediate_dir(&root, false).expect("The intermediate should exist");
+        let bytes = ["main.prt", "other.prt"].iter()
+            .map(|name| fs::metadata(intermediate.join(name)).expect("The intermediate should be saved").len())
+            .sum::<u64>();
+
+        let cleaned = Compiler::clean(&root, false).expect("Only cached tokens are removed");
+        assert_eq!((cleaned.files, cleaned.bytes), (2, bytes));
+        assert!(!intermediate.exists());
+        assert_eq!(Compiler::clean(&root, false).map(|cleaned| cleaned.files), Ok(0));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn strict_mode_fails_on_warnings() {
+        let root = project("strict", "so reverse as [1].");
+        let parse = |strict: bool| Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.strict(strict).parse(DEFAULT_MAX_ERRORS))
+            .map(|compiler| compiler.program().0.len());
+
+        assert_eq!(parse(false), Ok(1));
+        match parse(true) {
+            Err(CompilerError::PromotedWarning(warning)) => assert!(warning.contains("shadows the builtin verb \"reverse\""), "{warning}"),
+            other => panic!("Expected a promoted warning but found {other:?}"),
+        }
+        assert!(parse(true).is_err_and(|error| error.to_string().starts_with("[E0010] ")));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn other_files_are_skipped_with_a_warning() {
+        let root = project("skipped", "[1].");
+        let source_dir = root.join(Project::SOURCE_DIR);
+        fs::write(source_dir.join("notes.txt"), "[2].").expect("Failed to write the notes");
+
+        let compiler = Compiler::new(&root).expect("main.prs is still a source");
+        assert_eq!(compiler.warnings(), [Rc::from("Skipped notes.txt, only .prs files are compiled")]);
+
+        fs::remove_file(source_dir.join("main.prs")).expect("Failed to remove the source");
+        let error = Compiler::new(&root).err().expect("Only notes are left");
+        assert!(error.to_string().contains("No .prs sources found in"), "{error}");
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn environment_lists_the_declared_variables() {
+        let root = project("environment", "so count is number as [3]. fixed so name as \"cat\". count as [4].");
+        let compiler = Compiler::new(&root)
+            .and_then(Compiler::scan)
+            .and_then(|compiler| compiler.parse(DEFAULT_MAX_ERRORS))
+            .and_then(Compiler::evaluate)
+            .expect("The project should evaluate");
+
+        let dump = compiler.environment().to_string();
+        let lines = dump.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"count: number = 4"), "{dump}");
+        assert!(lines.contains(&"fixed name: text = cat"), "{dump}");
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+}
@@ -1,6 +1,7 @@
 use std::convert::From;
 use std::fmt;
 
+use crate::compilation::precedent::Precedent;
 use crate::compilation::token::TokenType;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -24,8 +25,8 @@ impl fmt::Display for Conjunction {
             Conjunction::GreaterEqual => write!(f, ">="),
             Conjunction::Less => write!(f, "<"),
             Conjunction::LessEqual => write!(f, "<="),
-            Conjunction::Equal => write!(f, "=="),
-            Conjunction::NotEqual => write!(f, "!="),
+            Conjunction::Equal => write!(f, "="),
+            Conjunction::NotEqual => write!(f, "~"),
             Conjunction::Or => write!(f, "or"),
             Conjunction::And => write!(f, "and"),
         }
@@ -47,3 +48,19 @@ impl From<TokenType> for Conjunction {
         }
     }
 }
+
+impl Conjunction {
+    pub fn precedent(&self) -> Precedent {
+        match self {
+            Conjunction::None => Precedent::None,
+            Conjunction::Greater => TokenType::Greater.precedent(),
+            Conjunction::GreaterEqual => TokenType::GreaterEqual.precedent(),
+            Conjunction::Less => TokenType::Less.precedent(),
+            Conjunction::LessEqual => TokenType::LessEqual.precedent(),
+            Conjunction::Equal => TokenType::Equal.precedent(),
+            Conjunction::NotEqual => TokenType::Tilde.precedent(),
+            Conjunction::Or => TokenType::Or.precedent(),
+            Conjunction::And => TokenType::And.precedent(),
+        }
+    }
+}
@@ -2,19 +2,130 @@ use std::fmt;
 use std::rc::Rc;
 use serde::{Deserialize, Serialize};
 
+use crate::compilation::errors::{ErrorCode, EvaluationError};
+use crate::compilation::evaluation::Evaluation;
+
 #[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
 pub enum Datatype {
     Number,
     Text,
     Boolean,
     Custom(Rc<str>),
+    Any,
+    // A datatype whose values must also satisfy a constraint, checked whenever one is stored
+    Constrained(Box<Datatype>, Constraint),
 }
 
 impl fmt::Display for Datatype {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Datatype::Number => write!(f, "number"),
+            Datatype::Text => write!(f, "text"),
+            Datatype::Boolean => write!(f, "notion"),
             Datatype::Custom(datatype) => write!(f, "{datatype}"),
-            _ => write!(f, "{self:?}"),
+            Datatype::Any => write!(f, "any"),
+            Datatype::Constrained(datatype, constraint) => write!(f, "{constraint} {datatype}"),
+        }
+    }
+}
+
+impl Datatype {
+    // Constraints are only checked against values, so they do not change which datatypes match
+    pub fn parity(&self, other: &Datatype) -> Result<(), EvaluationError> {
+        let matched = match (self, other) {
+            (Datatype::Constrained(datatype, _), other) => return datatype.parity(other),
+            (this, Datatype::Constrained(datatype, _)) => return this.parity(datatype),
+            (Datatype::Any, _) | (_, Datatype::Any) => true,
+            (Datatype::Custom(name), Datatype::Custom(other_name)) => name.as_ref() == other_name.as_ref(),
+            (this, other) => this == other,
+        };
+
+        if matched {
+            Ok(())
+        } else {
+            Err(EvaluationError::coded(ErrorCode::MismatchedType, &format!("Expected {self} but found {other}")))
+        }
+    }
+
+    // Collectives and verbs have no datatype of their own, so only `any` can hold them
+    pub fn admit(&self, value: &Evaluation) -> Result<(), EvaluationError> {
+        match value.datatype() {
+            Some(value_type) => self.parity(&value_type)?,
+            None if self.is_any() => {},
+            None => return Err(EvaluationError::coded(ErrorCode::MismatchedType, &format!("Expected {self} but found {value}"))),
+        }
+        self.validate(value)
+    }
+
+    fn is_any(&self) -> bool {
+        match self {
+            Datatype::Constrained(datatype, _) => datatype.is_any(),
+            datatype => *datatype == Datatype::Any,
+        }
+    }
+
+    pub fn validate(&self, value: &Evaluation) -> Result<(), EvaluationError> {
+        match self {
+            Datatype::Constrained(datatype, constraint) => {
+                datatype.validate(value)?;
+                constraint.check(value)
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
+pub enum Constraint {
+    NonEmpty,
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Constraint::NonEmpty => write!(f, "nonempty"),
+        }
+    }
+}
+
+impl Constraint {
+    pub fn check(&self, value: &Evaluation) -> Result<(), EvaluationError> {
+        let violation = match (self, value) {
+            (Constraint::NonEmpty, Evaluation::Text(text)) if text.is_empty() => "text \"\"",
+            (Constraint::NonEmpty, Evaluation::Collective(elements)) if elements.is_empty() => "collective ()",
+            _ => return Ok(()),
+        };
+
+        Err(EvaluationError::coded(ErrorCode::ViolatedConstraint, &format!("Expected {self} value but found {violation}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::intepreter::run_source;
+
+    #[test]
+    fn nonempty_text_is_checked_on_every_store() {
+        assert_eq!(run_source("so name is nonempty text as \"Ruffz\". name."), Ok(Evaluation::Text("Ruffz".into())));
+
+        for source in ["so name is nonempty text as \"\".", "so name is nonempty text as \"Ruffz\". name as \"\"."] {
+            let error = run_source(source).expect_err("An empty text violates the constraint");
+            assert!(error.to_string().contains(&ErrorCode::ViolatedConstraint.to_string()), "{source}: {error}");
         }
     }
+
+    #[test]
+    fn constraint_keeps_the_datatype() {
+        let error = run_source("so name is nonempty text as [1].").expect_err("A number is not text");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+        assert!(Constraint::NonEmpty.check(&Evaluation::Collective([].into())).is_err());
+    }
+
+    #[test]
+    fn any_holds_number_and_text() {
+        assert_eq!(run_source("so value is any as [1]. value as \"one\". value."), Ok(Evaluation::Text("one".into())));
+        assert_eq!(Datatype::Any.parity(&Datatype::Number), Ok(()));
+        assert_eq!(Datatype::Text.parity(&Datatype::Any), Ok(()));
+    }
 }
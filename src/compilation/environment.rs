@@ -1,15 +1,43 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 
 use crate::compilation::datatype::Datatype;
+use crate::compilation::errors::{ErrorCode, EvaluationError};
 use crate::compilation::evaluation::Evaluation;
 
-#[derive(Default, Debug)]
+// Bindings set up by the interpreter itself, which user code may read but never reassign
+const RESERVED_NAMES: [&str; 2] = ["it", "super"];
+
+#[derive(Default, Clone, Debug)]
 pub struct Environment {
     pub outer: Option<Box<Environment>>,
     values: HashMap<Variable, Evaluation>,
+    // While a snapshot is open, each changed binding is logged with what it replaced
+    journal: Vec<(Variable, Option<(Variable, Evaluation)>)>,
+    open: usize,
+}
+
+// One binding per line sorted by name so the output is stable, followed by those of the outer scopes
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bindings = self.values.iter().collect::<Vec<_>>();
+        bindings.sort_by(|(variable, _), (other, _)| variable.name.cmp(&other.name));
+        for (variable, value) in bindings {
+            writeln!(f, "{variable} = {value}")?;
+        }
+
+        match &self.outer {
+            Some(outer) => write!(f, "{outer}"),
+            None => Ok(()),
+        }
+    }
 }
 
+// The journal length of every scope when the snapshot was taken, innermost first
+#[derive(Debug)]
+pub struct Snapshot(Vec<usize>);
+
 impl Environment {
     pub fn within_scope(outer: Environment) -> Self {
         Self {
@@ -18,18 +46,48 @@ impl Environment {
         }
     }
     
-    pub fn define(&mut self, var: Variable, value: Evaluation) {
+    // A fixed variable may only be shadowed from an inner scope, never redeclared in its own
+    pub fn define(&mut self, var: Variable, value: Evaluation) -> Result<(), EvaluationError> {
+        if self.values.get_key_value(&var).is_some_and(|(declared, _)| declared.fixed) {
+            let msg = format!("Unable to redeclare fixed variable \"{}\"", var.name);
+            return Err(EvaluationError::coded(ErrorCode::FixedAssignment, &msg));
+        }
+
+        // Remove first so a redeclaration also replaces the key and its fixed flag
+        let previous = self.values.remove_entry(&var);
+        if self.open > 0 {
+            self.journal.push((var.clone(), previous));
+        }
         self.values.insert(var, value);
+        Ok(())
     }
 
-    pub fn assign(&mut self, var: Variable, value: Evaluation) -> Result<(), String> {
-        if self.contains_var(&var) {
+    pub fn assign(&mut self, var: Variable, value: Evaluation) -> Result<(), EvaluationError> {
+        if is_reserved(&var.name) {
+            return Err(EvaluationError::new(&format!("Unable to assign to reserved name \"{}\"", var.name)));
+        }
+
+        if let Some((declared, _)) = self.values.get_key_value(&var) {
+            if declared.fixed {
+                let msg = format!("Unable to assign to fixed variable \"{}\"", var.name);
+                return Err(EvaluationError::coded(ErrorCode::FixedAssignment, &msg));
+            }
+
+            if let Some(datatype) = &declared.datatype {
+                datatype.admit(&value)?;
+            }
+
+            if self.open > 0 {
+                let previous = self.values.get_key_value(&var).map(|(declared, value)| (declared.clone(), value.clone()));
+                self.journal.push((var.clone(), previous));
+            }
             self.values.insert(var, value);
             Ok(())
         } else if let Some(env) = self.outer.as_mut() {
             env.assign(var, value)
         } else {
-            Err(var.name)
+            let msg = format!("Undefined variable \"{}\".", var.name);
+            Err(EvaluationError::coded(ErrorCode::UndefinedVariable, &msg))
         }
     }
 
@@ -42,12 +100,54 @@ impl Environment {
     pub fn contains_var(&self, var: &Variable) -> bool {
         self.values.contains_key(var)
     }
+
+    // Only the bindings changed from here on are kept aside, nothing is copied up front
+    pub fn snapshot(&mut self) -> Snapshot {
+        let mut marks = Vec::new();
+        let mut scope = Some(self);
+        while let Some(env) = scope {
+            env.open += 1;
+            marks.push(env.journal.len());
+            scope = env.outer.as_deref_mut();
+        }
+        Snapshot(marks)
+    }
+
+    // Discards every definition and assignment made since the snapshot was taken
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        let mut scope = Some(self);
+        for mark in snapshot.0 {
+            let Some(env) = scope else { break };
+            while env.journal.len() > mark {
+                if let Some((var, previous)) = env.journal.pop() {
+                    env.values.remove(&var);
+                    if let Some((declared, value)) = previous {
+                        env.values.insert(declared, value);
+                    }
+                }
+            }
+            env.open -= 1;
+            scope = env.outer.as_deref_mut();
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+pub fn is_reserved(name: &str) -> bool {
+    RESERVED_NAMES.contains(&name)
+}
+
+#[derive(Eq, Clone, Debug)]
 pub struct Variable {
     pub name: String,
     pub datatype: Option<Datatype>,
+    pub fixed: bool,
+}
+
+// Variables are identified by name only, so a lookup with `Variable::with` finds a typed declaration
+impl PartialEq for Variable {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
 }
 
 impl Hash for Variable {
@@ -56,18 +156,104 @@ impl Hash for Variable {
     }
 }
 
-impl Variable {
-    pub fn new(name: &str, datatype: &Datatype) -> Self {
-        Self {
-            name: name.to_string(),
-            datatype: Some(datatype.clone())
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.fixed {
+            write!(f, "fixed ")?;
+        }
+        match &self.datatype {
+            Some(datatype) => write!(f, "{}: {datatype}", self.name),
+            None => write!(f, "{}", self.name),
         }
     }
+}
 
+impl Variable {
     pub fn with(name: &str) -> Self {
         Self {
             name: name.to_string(),
-            datatype: None
+            datatype: None,
+            fixed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::compilation::analysis::validate;
+    use crate::compilation::intepreter::run_source;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    #[test]
+    fn reserved_names_cannot_be_assigned() {
+        for source in ["it as [1].", "super as [2]."] {
+            let error = run_source(source).expect_err("Reserved names are read only");
+            assert!(error.to_string().contains("reserved name"), "{source}: {error}");
         }
     }
+
+    #[test]
+    fn reserved_names_cannot_be_declared() {
+        for source in ["so super is number as [1].", "so width and super as [1]."] {
+            let error = run_source(source).expect_err("Reserved names are set by the interpreter only");
+            assert!(error.to_string().contains("reserved name \"super\""), "{source}: {error}");
+        }
+    }
+
+    #[test]
+    fn reserved_names_cannot_be_assigned_in_a_verb_body() {
+        for source in ["verb reset for number { it as [5]. }", "verb reset for number when so super is number { hence it. }"] {
+            let scanner = Scanner::new(source, Rc::from([])).tokenize();
+            let statements = Parser::new(scanner.tokens().into())
+                .parse()
+                .expect("The source should parse")
+                .statements();
+
+            let error = validate(&statements).expect_err("Reserved names are read only in verbs too");
+            assert!(error.to_string().contains("reserved name"), "{source}: {error}");
+        }
+    }
+
+    #[test]
+    fn restore_rolls_back_changes() {
+        let mut environment = Environment::default();
+        environment.define(Variable { datatype: Some(Datatype::Number), ..Variable::with("x") }, Evaluation::Number(1.0)).expect("x is new");
+
+        let snapshot = environment.snapshot();
+        environment.assign(Variable::with("x"), Evaluation::Number(2.0)).expect("x is declared");
+        environment.define(Variable::with("y"), Evaluation::Boolean(true)).expect("y is new");
+        environment.restore(snapshot);
+
+        assert_eq!(environment.get("x"), Some(&Evaluation::Number(1.0)));
+        assert_eq!(environment.get("y"), None);
+        assert!(environment.assign(Variable::with("x"), Evaluation::Text("one".into())).is_err());
+    }
+
+    #[test]
+    fn restore_reaches_outer_scopes() {
+        let mut outer = Environment::default();
+        outer.define(Variable::with("x"), Evaluation::Number(1.0)).expect("x is new");
+        let mut environment = Environment::within_scope(outer);
+
+        let snapshot = environment.snapshot();
+        environment.assign(Variable::with("x"), Evaluation::Number(2.0)).expect("x is declared");
+        environment.restore(snapshot);
+
+        assert_eq!(environment.get("x"), Some(&Evaluation::Number(1.0)));
+    }
+
+    #[test]
+    fn fixed_variable_is_shadowed_only_in_an_inner_scope() {
+        let mut outer = Environment::default();
+        outer.define(Variable { fixed: true, ..Variable::with("x") }, Evaluation::Number(1.0)).expect("x is new");
+        assert!(outer.define(Variable::with("x"), Evaluation::Number(2.0)).is_err());
+
+        let mut environment = Environment::within_scope(outer);
+        environment.define(Variable::with("x"), Evaluation::Number(2.0)).expect("An inner scope may shadow x");
+        assert_eq!(environment.get("x"), Some(&Evaluation::Number(2.0)));
+        assert_eq!(environment.outer.as_ref().and_then(|outer| outer.get("x")), Some(&Evaluation::Number(1.0)));
+    }
 }
@@ -7,11 +7,17 @@ pub enum CompilerError {
     None,
     SourceError(Rc<str>),
     LexicalError(Rc<str>),
+    // The source parsed, but its statements do not make sense where they are
+    SemanticError(Rc<str>),
+    // A warning that strict mode does not let pass
+    PromotedWarning(Rc<str>),
     RuntimeError(EvaluationError),
+    TypeError(EvaluationError),
     MultiError(Rc<[CompilerError]>),
 }
 
 impl CompilerError {
+    #[allow(clippy::should_implement_trait)]
     pub fn add(self, error: CompilerError) -> CompilerError {
         match self {
             CompilerError::None => error,
@@ -29,9 +35,12 @@ impl fmt::Display for CompilerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             CompilerError::None => write!(f, "Non error"),
-            CompilerError::SourceError(error) => write!(f, "Failed to read file: {}", error),
-            CompilerError::LexicalError(error) => write!(f, "Parsed with error(s): {}", error),
+            CompilerError::SourceError(error) => write!(f, "[{}] Failed to read file: {}", ErrorCode::UnreadableSource, error),
+            CompilerError::LexicalError(error) => write!(f, "[{}] Parsed with error(s): {}", ErrorCode::InvalidSyntax, error),
+            CompilerError::SemanticError(error) => write!(f, "[{}] Analysed with error(s): {}", ErrorCode::InvalidStructure, error),
+            CompilerError::PromotedWarning(warning) => write!(f, "[{}] Warning treated as error: {}", ErrorCode::PromotedWarning, warning),
             CompilerError::RuntimeError(error) => write!(f, "Evaluated with {} error(s): {}", error.error_count(), error),
+            CompilerError::TypeError(error) => write!(f, "Checked with {} type error(s): {}", error.error_count(), error),
             CompilerError::MultiError(errors) => write!(f, "Compiled with {} error(s):\n    {}", errors.len(), 
                 errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n    ")),
         }
@@ -76,10 +85,15 @@ impl fmt::Display for EvaluationError {
 impl EvaluationError {
     pub fn new(detail: &str) -> Self {
         Self {
-            details: vec![detail.into()].into()
+            details: vec![detail.into()]
         }
     }
 
+    pub fn coded(code: ErrorCode, detail: &str) -> Self {
+        Self::new(&format!("[{code}] {detail}"))
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, detail: &str) -> Self {
         self.details.push(detail.into());
         Self {
@@ -105,3 +119,174 @@ impl EvaluationError {
         self.details.len().try_into().unwrap_or_default()
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    UndefinedVariable,
+    VoidInitializer,
+    InvalidPhrase,
+    UnreadableSource,
+    InvalidSyntax,
+    MismatchedType,
+    FixedAssignment,
+    ViolatedConstraint,
+    InvalidStructure,
+    PromotedWarning,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let number = match self {
+            ErrorCode::UndefinedVariable => 1,
+            ErrorCode::VoidInitializer => 2,
+            ErrorCode::InvalidPhrase => 3,
+            ErrorCode::UnreadableSource => 4,
+            ErrorCode::InvalidSyntax => 5,
+            ErrorCode::MismatchedType => 6,
+            ErrorCode::FixedAssignment => 7,
+            ErrorCode::ViolatedConstraint => 8,
+            ErrorCode::InvalidStructure => 9,
+            ErrorCode::PromotedWarning => 10,
+        };
+        write!(f, "E{:04}", number)
+    }
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 10] = [
+        ErrorCode::UndefinedVariable,
+        ErrorCode::VoidInitializer,
+        ErrorCode::InvalidPhrase,
+        ErrorCode::UnreadableSource,
+        ErrorCode::InvalidSyntax,
+        ErrorCode::MismatchedType,
+        ErrorCode::FixedAssignment,
+        ErrorCode::ViolatedConstraint,
+        ErrorCode::InvalidStructure,
+        ErrorCode::PromotedWarning,
+    ];
+
+    pub fn find(code: &str) -> Option<Self> {
+        ErrorCode::ALL.iter()
+            .find(|known| known.to_string().eq_ignore_ascii_case(code.trim()))
+            .copied()
+    }
+
+    pub fn explain(&self) -> &'static str {
+        match self {
+            ErrorCode::UndefinedVariable => "\
+A variable was used before it was declared in the current scope or any outer scope.
+
+    price plus [5].
+
+Declare the variable with `so` before using it:
+
+    so price is number as [10].
+    price plus [5].",
+            ErrorCode::VoidInitializer => "\
+A `so` declaration was initialized with a phrase that evaluates to nothing (void).
+
+    so name is text as greet.
+
+Initialize it with a phrase that produces a value, or leave the initializer out:
+
+    so name is text as \"Ruffz\".
+    so name is text.",
+            ErrorCode::InvalidPhrase => "\
+The evaluator reached a phrase that the parser could not make sense of.
+This usually comes from a sentence with a missing noun or operator:
+
+    so total is number as [1] plus.
+
+Complete the phrase so every operator has its nouns:
+
+    so total is number as [1] plus [2].",
+            ErrorCode::UnreadableSource => "\
+A project file or directory could not be read or written.
+Make sure the command is run from the project directory, that the `source`
+directory exists, and that the `.prs` files are readable:
+
+    stitch new my_book
+    stitch build",
+            ErrorCode::InvalidSyntax => "\
+The source does not follow the grammar of Prose, e.g. a sentence without its closing dot:
+
+    so count is number as [1]
+
+End every sentence with a dot and close every `{` with a `}`:
+
+    so count is number as [1].",
+            ErrorCode::MismatchedType => "\
+A value does not match the datatype it was declared with:
+
+    so count is number as \"ten\".
+
+Use a value of the declared datatype, or declare it with the matching one:
+
+    so count is number as [10].
+    so count is text as \"ten\".",
+            ErrorCode::FixedAssignment => "\
+A variable declared with `fixed so` was assigned a new value:
+
+    fixed so limit is number as [5].
+    limit as [6].
+
+Declare it with a plain `so` if it needs to change:
+
+    so limit is number as [5].
+    limit as [6].",
+            ErrorCode::ViolatedConstraint => "\
+A value matches the declared datatype but not the constraint placed on it:
+
+    so name is nonempty text as \"\".
+
+Use a value that satisfies the constraint:
+
+    so name is nonempty text as \"Stitch\".",
+            ErrorCode::InvalidStructure => "\
+A statement parsed, but it does not make sense where it is written,
+e.g. a `hence` outside of any verb or adjective:
+
+    hence [5].
+
+Conclude with `hence` only inside the body of a verb or adjective:
+
+    verb five is number { hence [5]. }
+
+An empty phrase, verb, prefix or conjunction left in the parsed program
+is a bug in the parser rather than in the source, and is worth reporting
+together with the source that caused it.",
+            ErrorCode::PromotedWarning => "\
+The build is strict, so a warning fails it instead of only being printed,
+e.g. a definition shadowing a builtin verb:
+
+    so reverse is number as [1].
+
+Address the warning, here by picking another name, or build without `--strict`:
+
+    so backwards is number as [1].",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::intepreter::run_source;
+
+    #[test]
+    fn code_appears_in_the_rendered_error() {
+        let error = run_source("price plus [5].").expect_err("price is not declared");
+        assert!(error.to_string().starts_with("[E0001] "), "{error}");
+        assert!(CompilerError::RuntimeError(error).to_string().contains("[E0001]"));
+    }
+
+    #[test]
+    fn known_codes_are_explained() {
+        assert_eq!(ErrorCode::find("E0001"), Some(ErrorCode::UndefinedVariable));
+        assert_eq!(ErrorCode::find(" e0007 "), Some(ErrorCode::FixedAssignment));
+        assert_eq!(ErrorCode::find("E9999"), None);
+        assert!(ErrorCode::UndefinedVariable.explain().contains("so price is number as [10]."));
+        assert!(ErrorCode::ALL.iter().all(|code| !code.explain().is_empty()));
+    }
+}
@@ -1,5 +1,12 @@
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::rc::Rc;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::compilation::datatype::Datatype;
+use crate::compilation::errors::{ErrorCode, EvaluationError};
+use crate::compilation::routine::Routine;
 
 #[derive(Default, PartialEq, Clone, Debug)]
 pub enum Evaluation {
@@ -8,6 +15,8 @@ pub enum Evaluation {
     Text(Rc<str>),
     Boolean(bool),
     Custom(Rc<str>),
+    Collective(Rc<[Evaluation]>),
+    Action(Routine),
 }
 
 impl fmt::Display for Evaluation {
@@ -18,6 +27,155 @@ impl fmt::Display for Evaluation {
             Evaluation::Text(value) => write!(f, "{}", value),
             Evaluation::Boolean(value) => write!(f, "{}", value),
             Evaluation::Custom(typename) => write!(f, "{} {{..}}", typename),
+            Evaluation::Collective(values) => write!(f, "({})", values.iter()
+                .map(|v| v.to_string()).collect::<Vec<_>>().join(", ")),
+            Evaluation::Action(routine) => write!(f, "{}", routine),
+        }
+    }
+}
+
+impl Evaluation {
+    pub fn datatype(&self) -> Option<Datatype> {
+        match self {
+            Evaluation::Void => None,
+            Evaluation::Number(_) => Some(Datatype::Number),
+            Evaluation::Text(_) => Some(Datatype::Text),
+            Evaluation::Boolean(_) => Some(Datatype::Boolean),
+            Evaluation::Custom(typename) => Some(Datatype::Custom(typename.clone())),
+            Evaluation::Collective(_) => None,
+            Evaluation::Action(_) => None,
         }
     }
+
+    // Collectives are equal element by element, void only equals void, and other values must share a type
+    pub fn equal(&self, other: &Evaluation) -> Result<bool, EvaluationError> {
+        match (self, other) {
+            (Evaluation::Void, other) | (other, Evaluation::Void) => Ok(*other == Evaluation::Void),
+            (Evaluation::Collective(elements), Evaluation::Collective(other_elements)) => {
+                if elements.len() != other_elements.len() {
+                    return Ok(false);
+                }
+                for (element, other_element) in elements.iter().zip(other_elements.iter()) {
+                    if !element.equal(other_element)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            },
+            (Evaluation::Number(_), Evaluation::Number(_))
+            | (Evaluation::Text(_), Evaluation::Text(_))
+            | (Evaluation::Boolean(_), Evaluation::Boolean(_))
+            | (Evaluation::Action(_), Evaluation::Action(_)) => Ok(self == other),
+            (Evaluation::Custom(name), Evaluation::Custom(other_name)) if name == other_name => Ok(self == other),
+            (this, other) => {
+                let msg = format!("Cannot compare {} with {}", this.describe(), other.describe());
+                Err(EvaluationError::coded(ErrorCode::MismatchedType, &msg))
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Evaluation::Void => "void".to_string(),
+            Evaluation::Collective(_) => "collective".to_string(),
+            Evaluation::Action(_) => "verb".to_string(),
+            _ => self.datatype().map(|datatype| datatype.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+// Consistent with `equal`, so both zeros hash alike and verbs hash by the name of their routine
+impl Hash for Evaluation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Evaluation::Void => {},
+            Evaluation::Number(value) => {
+                let value = if *value == 0.0 { 0.0f32 } else { *value };
+                value.to_bits().hash(state);
+            },
+            Evaluation::Text(value) => value.hash(state),
+            Evaluation::Boolean(value) => value.hash(state),
+            Evaluation::Custom(typename) => typename.hash(state),
+            Evaluation::Collective(values) => values.hash(state),
+            Evaluation::Action(routine) => routine.hash(state),
+        }
+    }
+}
+
+// Routines cannot be serialized, so verbs are written as an object with only their name
+impl Serialize for Evaluation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Evaluation::Void => serializer.serialize_unit(),
+            Evaluation::Number(value) => serializer.serialize_f32(*value),
+            Evaluation::Text(value) => serializer.serialize_str(value),
+            Evaluation::Boolean(value) => serializer.serialize_bool(*value),
+            Evaluation::Custom(typename) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("noun", typename.as_ref())?;
+                map.end()
+            },
+            Evaluation::Collective(values) => serializer.collect_seq(values.iter()),
+            Evaluation::Action(routine) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("verb", routine.name.as_ref())?;
+                map.end()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use crate::compilation::intepreter::run_source;
+
+    #[test]
+    fn collectives_are_equal_element_by_element() {
+        assert_eq!(run_source("([1], ([2], \"a\")) = ([1], ([2], \"a\"))."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run_source("([1], [2]) = ([2], [1])."), Ok(Evaluation::Boolean(false)));
+        assert_eq!(run_source("([1], [2]) = ([1], [2], [3])."), Ok(Evaluation::Boolean(false)));
+
+        let error = run_source("([1], [2]) = ([1], \"b\").").expect_err("Elements of different types cannot be compared");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn data_serializes_as_plain_json() {
+        let value = run_source("([1], \"a\", true, ([2.5], \"b\")).").expect("The collective should evaluate");
+        let json = serde_json::to_string(&value).expect("The values should serialize");
+        let parsed = serde_json::from_str::<serde_json::Value>(&json).expect("The JSON should parse back");
+        assert_eq!(parsed, serde_json::json!([1.0, "a", true, [2.5, "b"]]));
+
+        let verb = run_source("reverse.").expect("reverse is a builtin");
+        assert_eq!(serde_json::to_value(&verb).ok(), Some(serde_json::json!({ "verb": "reverse" })));
+    }
+
+    fn hashed(value: &Evaluation) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_values_hash_alike() {
+        let pairs = [
+            (Evaluation::Number(0.0), Evaluation::Number(-0.0)),
+            (Evaluation::Text("a".into()), Evaluation::Text(String::from("a").into())),
+            (
+                Evaluation::Collective([Evaluation::Number(-0.0), Evaluation::Text("a".into())].into()),
+                Evaluation::Collective([Evaluation::Number(0.0), Evaluation::Text("a".into())].into()),
+            ),
+        ];
+        for (left, right) in pairs {
+            assert_eq!(left.equal(&right), Ok(true), "{left} = {right}");
+            assert_eq!(hashed(&left), hashed(&right), "{left} and {right}");
+        }
+
+        let reverse = run_source("reverse.").expect("reverse is a builtin");
+        assert_eq!(hashed(&reverse), hashed(&run_source("reverse.").expect("reverse is a builtin")));
+        assert_ne!(hashed(&Evaluation::Number(1.0)), hashed(&Evaluation::Text("1".into())));
+    }
 }
@@ -1,78 +1,620 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::io::{self, Write};
+use std::rc::Rc;
 
+use crate::compilation::builtins::add_builtin_features;
+use crate::compilation::conjunction::Conjunction;
 use crate::compilation::datatype::Datatype;
-use crate::compilation::environment::{Environment, Variable};
-use crate::compilation::errors::EvaluationError;
+use crate::compilation::environment::{is_reserved, Environment, Variable};
+use crate::compilation::errors::{ErrorCode, EvaluationError};
 use crate::compilation::evaluation::Evaluation;
 use crate::compilation::phrase::Phrase;
+use crate::compilation::prefix::Prefix;
 use crate::compilation::primitive::Primitive;
+use crate::compilation::routine::Routine;
 use crate::compilation::statement::Statement;
+use crate::compilation::verb::Verb;
 
 #[derive(Default)]
-pub struct Intepreter {
+pub struct Intepreter<'w> {
     environment: RefCell<Environment>,
+    tracer: Tracer<'w>,
 }
 
-impl Intepreter {
-    pub fn new() -> Self {
-        Default::default()
+impl<'w> Intepreter<'w> {
+    // The builtins live in a scope of their own, so the program may shadow them but not replace them
+    pub fn new(trace: bool) -> Self {
+        let mut builtins = Environment::default();
+        add_builtin_features(&mut builtins);
+        Self {
+            environment: RefCell::new(Environment::within_scope(builtins)),
+            tracer: Tracer { enabled: trace, ..Default::default() },
+        }
+    }
+
+    // The trace goes to the given writer instead of stderr
+    pub fn trace_to(self, writer: &'w mut dyn Write) -> Self {
+        Self {
+            tracer: Tracer { writer: RefCell::new(Some(writer)), ..self.tracer },
+            ..self
+        }
     }
 
     pub fn execute(&mut self, statement : &Statement) -> Result<Evaluation, EvaluationError> {
+        self.tracer.enter(&format!("> {statement}"));
+        let result = self.execute_statement(statement);
+        self.tracer.exit(&trace_result(&result));
+        result
+    }
+
+    pub fn environment(&self) -> Environment {
+        self.environment.borrow().clone()
+    }
+
+    // Runs the statements in order and gives the value of the last one that was not void
+    pub fn run(&mut self, statements: &[Statement]) -> Result<Evaluation, EvaluationError> {
+        let mut last = Evaluation::Void;
+        for statement in statements {
+            let value = self.execute(statement)?;
+            if value != Evaluation::Void {
+                last = value;
+            }
+        }
+        Ok(last)
+    }
+
+    fn execute_statement(&mut self, statement : &Statement) -> Result<Evaluation, EvaluationError> {
+        let tracer = &self.tracer;
         match statement {
-            Statement::Noun { name, super_type, body } => todo!(),
-            Statement::Verb { name, hence_type, subject_type, object_types, body } => todo!(),
-            Statement::Adjective { name, subject_type, body } => todo!(),
-            Statement::So { name, datatype, initializer } => declare_so(name, datatype, initializer.as_ref(), &mut self.environment.borrow_mut()),
-            Statement::Phrase(phrase) => evaluate(phrase, &mut self.environment.borrow_mut()),
-            Statement::Hence(phrase) => todo!(),
+            Statement::Noun { name, .. } => {
+                tracer.log(&format!("define noun {name}"));
+                Ok(Evaluation::Void)
+            },
+            Statement::Verb { name, .. } => define_verb(name, &mut self.environment.borrow_mut(), tracer),
+            Statement::Adjective { name, .. } => {
+                tracer.log(&format!("define adjective {name}"));
+                Ok(Evaluation::Void)
+            },
+            Statement::So { names, datatype, initializer, fixed } => declare_so(names, datatype.as_ref(), initializer.as_ref(), *fixed, &mut self.environment.borrow_mut(), tracer),
+            Statement::Phrase(phrase) => evaluate(phrase, &mut self.environment.borrow_mut(), tracer),
+            // Verb and adjective bodies are not run yet, so every hence reaching here is outside of one
+            Statement::Hence(_) => {
+                let msg = format!("\"{statement}\" is outside of a verb or adjective");
+                Err(EvaluationError::coded(ErrorCode::InvalidStructure, &msg))
+            },
+        }
+    }
+}
+
+// Prints the evaluation steps when enabled, indented by how deep the verbs are nested,
+// to stderr unless another writer is given
+#[derive(Default)]
+struct Tracer<'w> {
+    enabled: bool,
+    depth: Cell<usize>,
+    writer: RefCell<Option<&'w mut dyn Write>>,
+}
+
+impl Tracer<'_> {
+    fn log(&self, message: &str) {
+        if !self.enabled {
+            return;
         }
+
+        let indent = "    ".repeat(self.depth.get());
+        // A trace that cannot be written is not worth failing the evaluation for
+        let _ = match self.writer.borrow_mut().as_mut() {
+            Some(writer) => writeln!(writer, "{indent}{message}"),
+            None => writeln!(io::stderr(), "{indent}{message}"),
+        };
     }
+
+    fn enter(&self, message: &str) {
+        self.log(message);
+        self.depth.set(self.depth.get() + 1);
+    }
+
+    fn exit(&self, message: &str) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+        self.log(message);
+    }
+}
+
+fn trace_result(result: &Result<Evaluation, EvaluationError>) -> String {
+    match result {
+        Ok(value) => format!("= {value}"),
+        Err(_) => "= error".to_string(),
+    }
+}
+
+// The verb is registered so its name resolves from anywhere, but calling it reports that its body is not run yet
+fn define_verb(name: &str, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let variable = Variable { fixed: true, ..Variable::with(name) };
+    tracer.log(&format!("define verb {name}"));
+    environment.define(variable, Evaluation::Action(Routine::new_native(name, unevaluated_body)))?;
+    Ok(Evaluation::Void)
+}
+
+fn unevaluated_body(_subject: &Evaluation, _object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+    Err(EvaluationError::coded(ErrorCode::InvalidPhrase, "The body of a verb defined in source is not evaluated yet"))
 }
 
-fn declare_so(name: &str, datatype: &Datatype, initializer : Option<&Phrase>, environment: &mut Environment) -> Result<Evaluation, EvaluationError> {
-    let variable = Variable::new(name, datatype);
-    match initializer {
+fn declare_so(names: &[Rc<str>], datatype: Option<&Datatype>, initializer : Option<&Phrase>, fixed: bool, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    if let Some(name) = names.iter().find(|name| is_reserved(name)) {
+        return Err(EvaluationError::new(&format!("Unable to declare reserved name \"{name}\"")));
+    }
+
+    let value = match initializer {
         None => {
-            environment.define(variable, Evaluation::Void);
-            Ok(Evaluation::Void)
-        },
-        Some(phrase) => {
-            let result = evaluate(phrase, environment)?;
-            match result {
-                Evaluation::Void => Err(EvaluationError::new("Unable to initialize so declaration as void")),
-                value => {
-                    environment.define(variable, value);
-                    Ok(Evaluation::Void)
-                },
+            for name in names {
+                let variable = Variable { datatype: datatype.cloned(), fixed, ..Variable::with(name) };
+                environment.define(variable, Evaluation::Void)?;
             }
+            return Ok(Evaluation::Void);
         },
+        Some(phrase) => evaluate(phrase, environment, tracer)?,
+    };
+
+    // A collective with one element per name is spread over the names, anything else is shared by all of them
+    let values = match &value {
+        Evaluation::Collective(elements) if names.len() > 1 && elements.len() == names.len() => elements.to_vec(),
+        value => vec![value.clone(); names.len()],
+    };
+
+    for (name, value) in names.iter().zip(values) {
+        define_so(name, datatype, value, fixed, environment, tracer)?;
     }
+    Ok(Evaluation::Void)
 }
 
-fn evaluate(phrase : &Phrase, environment: &mut Environment) -> Result<Evaluation, EvaluationError> {
+fn define_so(name: &str, datatype: Option<&Datatype>, value: Evaluation, fixed: bool, environment: &mut Environment, tracer: &Tracer) -> Result<(), EvaluationError> {
+    if value == Evaluation::Void {
+        return Err(EvaluationError::coded(ErrorCode::VoidInitializer, "Unable to initialize so declaration as void"));
+    }
+
+    if let Some(datatype) = datatype {
+        datatype.admit(&value)
+            .map_err(|error| EvaluationError::new(&format!("Invalid initializer for so declaration \"{name}\"")).concat(error))?;
+    }
+
+    // Without a declared datatype, the variable takes the datatype of its initial value
+    let variable = Variable { datatype: datatype.cloned().or_else(|| value.datatype()), fixed, ..Variable::with(name) };
+    tracer.log(&format!("define {name} as {value}"));
+    environment.define(variable, value)
+}
+
+fn evaluate(phrase : &Phrase, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
     match phrase {
-        Phrase::None => Err(EvaluationError::new("None phrase")),
-        Phrase::Primary(primitive) => evaluate_primitive(primitive, environment),
-        Phrase::Postfix { noun, adjective } => todo!(),
-        Phrase::Prefix { prefix, noun } => todo!(),
-        Phrase::Action { subject, verb, object } => todo!(),
-        Phrase::Condition { left, conjunction, right } => todo!(),
+        Phrase::None => Err(EvaluationError::coded(ErrorCode::InvalidPhrase, "None phrase")),
+        Phrase::Primary(primitive) => evaluate_primitive(primitive, environment, tracer),
+        Phrase::Postfix { noun, adjective } => evaluate_postfix(noun, adjective, environment, tracer),
+        Phrase::Prefix { prefix, noun } => evaluate_prefix(prefix, noun, environment, tracer),
+        Phrase::Action { subject, verb, object } => evaluate_action(subject.as_deref(), verb, object.as_deref(), environment, tracer),
+        Phrase::Condition { left, conjunction, right } => evaluate_condition(left, conjunction, right, environment, tracer),
+    }
+}
+
+fn evaluate_prefix(prefix: &Prefix, noun: &Phrase, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    match prefix {
+        Prefix::Negation => match evaluate(noun, environment, tracer)? {
+            Evaluation::Number(number) => Ok(Evaluation::Number(-number)),
+            value => Err(EvaluationError::new(&format!("Expected a number as the operand of {prefix} but found {value}"))),
+        },
+        Prefix::Not => match evaluate(noun, environment, tracer)? {
+            Evaluation::Boolean(notion) => Ok(Evaluation::Boolean(!notion)),
+            value => Err(EvaluationError::new(&format!("Expected a notion as the operand of {prefix} but found {value}"))),
+        },
+        Prefix::Adjective(adjective) => Err(EvaluationError::coded(ErrorCode::InvalidPhrase, &format!("Adjective \"{adjective}\" is not supported in evaluation yet"))),
+        Prefix::None => Err(EvaluationError::coded(ErrorCode::InvalidPhrase, "None prefix")),
+    }
+}
+
+// The adjective after `when` is a predicate, whatever it defines or assigns while it is checked is undone afterwards
+fn evaluate_postfix(noun: &Phrase, adjective: &Phrase, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let snapshot = environment.snapshot();
+    let holds = evaluate(adjective, environment, tracer);
+    environment.restore(snapshot);
+
+    // The noun is only evaluated when the adjective holds, otherwise it is void
+    match holds? {
+        Evaluation::Boolean(true) => evaluate(noun, environment, tracer),
+        Evaluation::Boolean(false) => Ok(Evaluation::Void),
+        value => Err(EvaluationError::coded(ErrorCode::MismatchedType, &format!("Expected a notion after when but found {value}"))),
+    }
+}
+
+fn evaluate_condition(left: &Phrase, conjunction: &Conjunction, right: &Phrase, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let left = evaluate(left, environment, tracer)?;
+
+    // `and` and `or` only evaluate their right side when the left one does not decide already
+    match (conjunction, &left) {
+        (Conjunction::And, Evaluation::Boolean(false)) => return Ok(Evaluation::Boolean(false)),
+        (Conjunction::Or, Evaluation::Boolean(true)) => return Ok(Evaluation::Boolean(true)),
+        _ => {},
+    }
+
+    let right = evaluate(right, environment, tracer)?;
+
+    match conjunction {
+        Conjunction::Equal => Ok(Evaluation::Boolean(left.equal(&right)?)),
+        Conjunction::NotEqual => Ok(Evaluation::Boolean(!left.equal(&right)?)),
+        Conjunction::Greater => numbers(conjunction, &left, &right).map(|(left, right)| Evaluation::Boolean(left > right)),
+        Conjunction::GreaterEqual => numbers(conjunction, &left, &right).map(|(left, right)| Evaluation::Boolean(left >= right)),
+        Conjunction::Less => numbers(conjunction, &left, &right).map(|(left, right)| Evaluation::Boolean(left < right)),
+        Conjunction::LessEqual => numbers(conjunction, &left, &right).map(|(left, right)| Evaluation::Boolean(left <= right)),
+        Conjunction::And | Conjunction::Or => match (&left, &right) {
+            (Evaluation::Boolean(_), Evaluation::Boolean(notion)) => Ok(Evaluation::Boolean(*notion)),
+            (left, right) => {
+                let msg = format!("Expected notions as the operands of {conjunction} but found {left} and {right}");
+                Err(EvaluationError::coded(ErrorCode::MismatchedType, &msg))
+            },
+        },
+        Conjunction::None => Err(EvaluationError::coded(ErrorCode::InvalidPhrase, "None conjunction")),
+    }
+}
+
+fn numbers(conjunction: &Conjunction, left: &Evaluation, right: &Evaluation) -> Result<(f32, f32), EvaluationError> {
+    match (left, right) {
+        (Evaluation::Number(left), Evaluation::Number(right)) => Ok((*left, *right)),
+        (left, right) => {
+            let msg = format!("Expected numbers as the operands of {conjunction} but found {left} and {right}");
+            Err(EvaluationError::coded(ErrorCode::MismatchedType, &msg))
+        },
+    }
+}
+
+fn evaluate_action(subject: Option<&Phrase>, verb: &Verb, object: Option<&Phrase>, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    match verb {
+        Verb::Assign => assign(subject, object, environment, tracer),
+        Verb::Action(name) => perform(name, subject, object, environment, tracer),
+        Verb::None => Err(EvaluationError::coded(ErrorCode::InvalidPhrase, "None verb")),
+        arithmetic => calculate(subject, arithmetic, object, environment, tracer),
+    }
+}
+
+// A void operand is most likely an uninitialized variable or the result of a void verb, so it is named as such
+fn calculate(subject: Option<&Phrase>, verb: &Verb, object: Option<&Phrase>, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let mut operands = [Evaluation::Void, Evaluation::Void];
+    for (operand, phrase) in operands.iter_mut().zip([subject, object]) {
+        if let Some(phrase) = phrase {
+            *operand = evaluate(phrase, environment, tracer)?;
+        }
+    }
+
+    let (left, right) = match &operands {
+        [Evaluation::Void, _] | [_, Evaluation::Void] => return Err(EvaluationError::new(&format!("Cannot use void as an operand of {verb}"))),
+        [Evaluation::Number(left), Evaluation::Number(right)] => (*left, *right),
+        [left, right] => return Err(EvaluationError::new(&format!("Expected numbers as the operands of {verb} but found {left} and {right}"))),
+    };
+
+    match verb {
+        Verb::Add => Ok(Evaluation::Number(left + right)),
+        Verb::Subtract => Ok(Evaluation::Number(left - right)),
+        Verb::Multiply => Ok(Evaluation::Number(left * right)),
+        Verb::Divide if right == 0.0 => Err(EvaluationError::new("Unable to divide by zero")),
+        Verb::Divide => Ok(Evaluation::Number(left / right)),
+        _ => Err(EvaluationError::new(&format!("{verb} is not an arithmetic verb"))),
+    }
+}
+
+fn perform(name: &str, subject: Option<&Phrase>, object: Option<&Phrase>, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let routine = match environment.get(name) {
+        Some(Evaluation::Action(routine)) => routine.clone(),
+        Some(_) => return Err(EvaluationError::new(&format!("\"{name}\" is not a verb"))),
+        None => return Err(EvaluationError::coded(ErrorCode::UndefinedVariable, &format!("Undefined verb \"{name}\"."))),
+    };
+
+    let subject = match subject {
+        Some(phrase) => evaluate(phrase, environment, tracer)?,
+        None => Evaluation::Void,
+    };
+    let object = match object {
+        Some(phrase) => evaluate(phrase, environment, tracer)?,
+        None => Evaluation::Void,
+    };
+
+    tracer.enter(&format!("enter verb {name} with {subject} and {object}"));
+    let result = routine.execute(&subject, &object);
+    tracer.exit(&format!("exit verb {name} {}", trace_result(&result)));
+    result
+}
+
+fn assign(subject: Option<&Phrase>, object: Option<&Phrase>, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let name = match subject {
+        Some(Phrase::Primary(Primitive::Variable(name))) => name.as_ref(),
+        Some(Phrase::Primary(Primitive::It)) => "it",
+        _ => return Err(EvaluationError::new("Unable to assign to a phrase that is not a variable")),
+    };
+
+    let value = match object {
+        Some(phrase) => evaluate(phrase, environment, tracer)?,
+        None => Evaluation::Void,
+    };
+
+    if value == Evaluation::Void {
+        return Err(EvaluationError::new(&format!("Unable to assign void to \"{name}\"")));
     }
+
+    tracer.log(&format!("assign {name} as {value}"));
+    environment.assign(Variable::with(name), value)?;
+    Ok(Evaluation::Void)
 }
 
-fn evaluate_primitive(primitive: &Primitive, environment: &Environment) -> Result<Evaluation, EvaluationError> {
+fn evaluate_primitive(primitive: &Primitive, environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
     match primitive {
         Primitive::Number(value) => Ok(Evaluation::Number(value.parse::<f32>().unwrap_or_default())),
         Primitive::Text(value) => Ok(Evaluation::Text(value.clone())),
         Primitive::True => Ok(Evaluation::Boolean(true)),
         Primitive::False => Ok(Evaluation::Boolean(false)),
-        Primitive::It => todo!(),
-        Primitive::Collective(phrases) => todo!(),
+        Primitive::It => Err(EvaluationError::coded(ErrorCode::UndefinedVariable, "\"it\" is only set inside a verb or adjective.")),
+        Primitive::Collective(phrases) => evaluate_collective(phrases, environment, tracer),
         Primitive::Variable(name) => if let Some(value) = environment.get(name) {
             Ok(value.clone())
         } else {
-            Err(EvaluationError::new(&format!("Undefined variable \"{}\".", name)))
+            Err(EvaluationError::coded(ErrorCode::UndefinedVariable, &format!("Undefined variable \"{}\".", name)))
         },
     }
-}
\ No newline at end of file
+}
+
+// Every element is evaluated so that all failing elements are reported together
+fn evaluate_collective(phrases: &[Phrase], environment: &mut Environment, tracer: &Tracer) -> Result<Evaluation, EvaluationError> {
+    let mut values = Vec::new();
+    let mut failure: Option<EvaluationError> = None;
+
+    for (index, phrase) in phrases.iter().enumerate() {
+        match evaluate(phrase, environment, tracer) {
+            Ok(value) => values.push(value),
+            Err(error) => {
+                let error = EvaluationError::new(&format!("In collective element {}", index + 1)).concat(error);
+                failure = Some(match failure {
+                    Some(previous) => previous.concat(error),
+                    None => error,
+                });
+            },
+        }
+    }
+
+    match failure {
+        Some(error) => Err(error),
+        None => Ok(Evaluation::Collective(values.into())),
+    }
+}
+
+// Scans, parses and runs a snippet on a fresh interpreter, giving the value of its last phrase
+#[cfg(test)]
+pub(crate) fn run_source(source: &str) -> Result<Evaluation, EvaluationError> {
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    let scanner = Scanner::new(source, Rc::from([])).tokenize();
+    let statements = Parser::new(scanner.tokens().into())
+        .parse()
+        .unwrap_or_else(|error| panic!("Failed to parse {source:?}: {error}"))
+        .statements();
+    Intepreter::new(false).run(&statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    fn run(source: &str) -> Result<Evaluation, EvaluationError> {
+        run_source(source)
+    }
+
+    #[test]
+    fn greater() {
+        assert_eq!(run("[1] > [2]."), Ok(Evaluation::Boolean(false)));
+        assert_eq!(run("[2] > [1]."), Ok(Evaluation::Boolean(true)));
+    }
+
+    #[test]
+    fn greater_equal() {
+        assert_eq!(run("[2] >= [2]."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("[1] >= [2]."), Ok(Evaluation::Boolean(false)));
+    }
+
+    #[test]
+    fn less() {
+        assert_eq!(run("[1] < [2]."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("[2] < [2]."), Ok(Evaluation::Boolean(false)));
+    }
+
+    #[test]
+    fn less_equal() {
+        assert_eq!(run("[2] <= [2]."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("[3] <= [2]."), Ok(Evaluation::Boolean(false)));
+    }
+
+    #[test]
+    fn comparing_text_is_an_error() {
+        let error = run("\"a\" < \"b\".").expect_err("Text is not ordered");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(run("-[2]."), Ok(Evaluation::Number(-2.0)));
+        assert_eq!(run("[3] - -[2]."), Ok(Evaluation::Number(5.0)));
+        assert_eq!(run("[3] minus -[2]."), Ok(Evaluation::Number(5.0)));
+        assert_eq!(run("[3] plus [2]."), Ok(Evaluation::Number(5.0)));
+    }
+
+    #[test]
+    fn not() {
+        assert_eq!(run("not true."), Ok(Evaluation::Boolean(false)));
+        assert_eq!(run("not ([1] > [2])."), Ok(Evaluation::Boolean(true)));
+    }
+
+    #[test]
+    fn adjective_is_an_error() {
+        let error = run("so x is number as [1]. the big x.").expect_err("Adjectives are not evaluated");
+        assert!(error.to_string().contains(&ErrorCode::InvalidPhrase.to_string()));
+    }
+
+    #[test]
+    fn collective_is_not_a_number() {
+        let error = run("so x is number as ([1], [2]).").expect_err("A collective is not a number");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+        assert_eq!(run("so x is any as ([1], [2]). x."), Ok(Evaluation::Collective([Evaluation::Number(1.0), Evaluation::Number(2.0)].into())));
+    }
+
+    #[test]
+    fn assigning_collective_to_number() {
+        let error = run("so x is number as [1]. x as ([1], [2]).").expect_err("A collective is not a number");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn assigning_verb_to_number() {
+        let error = run("so x is number as [1]. x as flatten.").expect_err("A verb is not a number");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn postfix_gives_the_noun_only_when_the_adjective_holds() {
+        assert_eq!(run("so x is number as [1]. x + [1] when x = [1]."), Ok(Evaluation::Number(2.0)));
+        assert_eq!(run("so x is number as [1]. x + [1] when x > [1]."), Ok(Evaluation::Void));
+
+        let error = run("so x is number as [1]. x when x.").expect_err("The adjective is not a notion");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn predicate_side_effects_are_rolled_back() {
+        let variable = |name: &str| Box::new(Phrase::Primary(Primitive::Variable(name.into())));
+        let number = |value: &str| Box::new(Phrase::Primary(Primitive::Number(value.into())));
+        // `[2] when (x as [5]) ~ [0]`, which the parser does not accept as an adjective yet
+        let assigning = Phrase::Condition {
+            left: Box::new(Phrase::Action { subject: Some(variable("x")), verb: Verb::Assign, object: Some(number("5")) }),
+            conjunction: Conjunction::NotEqual,
+            right: number("0"),
+        };
+        let predicate = Statement::Phrase(Phrase::Postfix { noun: number("2"), adjective: Box::new(assigning.clone()) });
+
+        let mut intepreter = Intepreter::new(false);
+        intepreter.execute(&Statement::So { names: Rc::from(["x".into()]), datatype: None, initializer: Some(*number("1")), fixed: false })
+            .expect("x should be declared");
+        assert_eq!(intepreter.execute(&predicate), Ok(Evaluation::Number(2.0)));
+        assert_eq!(intepreter.environment().get("x"), Some(&Evaluation::Number(1.0)));
+
+        // A plain condition is not a predicate, so what it assigns stays
+        assert_eq!(intepreter.execute(&Statement::Phrase(assigning)), Ok(Evaluation::Boolean(true)));
+        assert_eq!(intepreter.environment().get("x"), Some(&Evaluation::Number(5.0)));
+    }
+
+    #[test]
+    fn definitions_are_registered() {
+        assert_eq!(run("noun cat { } adjective big for number { hence true. } [1] + [1]."), Ok(Evaluation::Number(2.0)));
+
+        let error = run("verb greet for number { hence it. } [1] greet.").expect_err("Verb bodies are not evaluated");
+        assert!(error.to_string().contains(&ErrorCode::InvalidPhrase.to_string()));
+        assert!(error.to_string().contains("greet"));
+    }
+
+    #[test]
+    fn trace_is_indented() {
+        let scanner = Scanner::new("so x is number as [1]. x + [2].", Rc::from([])).tokenize();
+        let statements = Parser::new(scanner.tokens().into())
+            .parse()
+            .expect("The source should parse")
+            .statements();
+
+        let mut trace = Vec::new();
+        Intepreter::new(true).trace_to(&mut trace).run(&statements).expect("The source should evaluate");
+
+        let trace = String::from_utf8(trace).expect("The trace should be text");
+        let expected = [
+            "> so x is number as [1.0].",
+            "    define x as 1",
+            "= void",
+            "> x + [2.0].",
+            "= 3",
+        ];
+        assert_eq!(trace.lines().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn fixed_so_cannot_be_assigned() {
+        let error = run("fixed so x is number as [1]. x as [2].").expect_err("A fixed variable keeps its value");
+        assert!(error.to_string().contains(&ErrorCode::FixedAssignment.to_string()));
+        assert_eq!(run("so x is number as [1]. x as [2]. x."), Ok(Evaluation::Number(2.0)));
+    }
+
+    #[test]
+    fn fixed_so_cannot_be_redeclared() {
+        let error = run("fixed so x as [5]. so x as [6].").expect_err("A fixed variable keeps its value");
+        assert!(error.to_string().contains(&ErrorCode::FixedAssignment.to_string()));
+        assert!(run("fixed so x as [5]. fixed so x as [6].").is_err());
+        assert_eq!(run("so x as [5]. so x as [6]. x."), Ok(Evaluation::Number(6.0)));
+        assert_eq!(run("so reverse as [1]. reverse."), Ok(Evaluation::Number(1.0)));
+    }
+
+    #[test]
+    fn not_equal_text_and_notions() {
+        assert_eq!(run("\"a\" ~ \"b\"."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("\"a\" is not \"a\"."), Ok(Evaluation::Boolean(false)));
+        assert_eq!(run("true ~ false."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("true ~ true."), Ok(Evaluation::Boolean(false)));
+        assert!(run("\"a\" ~ true.").is_err());
+    }
+
+    #[test]
+    fn so_declares_several_names() {
+        assert_eq!(run("so width and height is number as ([2], [3]). width * height."), Ok(Evaluation::Number(6.0)));
+        assert_eq!(run("so a, b is number as [4]. a + b."), Ok(Evaluation::Number(8.0)));
+        assert_eq!(run("so a, b, and c is number as ([1], [2], [3]). a + b + c."), Ok(Evaluation::Number(6.0)));
+
+        let error = run("so a, b is number as ([1], \"two\").").expect_err("Each name takes its own element");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+    }
+
+    #[test]
+    fn and_or() {
+        assert_eq!(run("true and false."), Ok(Evaluation::Boolean(false)));
+        assert_eq!(run("false or true."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run("false and undefined."), Ok(Evaluation::Boolean(false)));
+    }
+
+    #[test]
+    fn so_infers_the_datatype_of_its_initializer() {
+        assert_eq!(run("so count as [5]. count + [1]."), Ok(Evaluation::Number(6.0)));
+        assert_eq!(run("so name as \"cat\". name."), Ok(Evaluation::Text("cat".into())));
+
+        let error = run("so count as [5]. count as \"five\".").expect_err("count was inferred as a number");
+        assert!(error.to_string().contains(&ErrorCode::MismatchedType.to_string()));
+
+        let scanner = Scanner::new("so count.", Rc::from([])).tokenize();
+        assert!(Parser::new(scanner.tokens().into()).parse().is_err());
+    }
+
+    #[test]
+    fn failing_collective_element_is_named() {
+        let error = run("([1], missing, [3]).").expect_err("missing is not defined").to_string();
+        assert!(error.contains("In collective element 2"), "{error}");
+        assert!(error.contains("Undefined variable \"missing\""), "{error}");
+        assert!(!error.contains("In collective element 1"), "{error}");
+    }
+
+    #[test]
+    fn void_operand_is_named() {
+        for source in ["(true assert) + [1].", "[2] * (true assert).", "so x is number. x - [1]."] {
+            let error = run(source).expect_err("Void is not a number").to_string();
+            assert!(error.contains("Cannot use void as an operand of"), "{source}: {error}");
+        }
+    }
+
+    #[test]
+    fn hence_and_it_outside_a_body_are_errors() {
+        for source in ["hence [5].", "hence.", "hence [5] when false."] {
+            let error = run(source).expect_err("There is no body to conclude").to_string();
+            assert!(error.contains("is outside of a verb or adjective"), "{source}: {error}");
+            assert!(error.contains(&ErrorCode::InvalidStructure.to_string()), "{source}: {error}");
+        }
+
+        let error = run("it + [1].").expect_err("it is not set").to_string();
+        assert!(error.contains(&ErrorCode::UndefinedVariable.to_string()), "{error}");
+    }
+}
@@ -5,51 +5,152 @@ use serde::{Deserialize, Serialize};
 
 use crate::projects::project::Project;
 use crate::compilation::errors::CompilerError;
+use crate::compilation::scanner::Options;
 use crate::compilation::source::Source;
 use crate::compilation::token::Token;
 
+// Caches written by a different stitch binary may follow other tokenization rules,
+// the suffix is bumped whenever the serialized token layout changes
+const COMPILER_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+tokens.5");
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Intermediate {
+    pub version: Rc<str>,
     pub hash: Rc<[u8]>,
+    pub modified: u128,
     pub tokens : Rc<[Token]>,
 }
 
+// The leading fields of a saved intermediate, enough to tell whether it is still valid without reading the tokens
+#[derive(Deserialize)]
+struct Header {
+    version: Rc<str>,
+    hash: Rc<[u8]>,
+    modified: u128,
+}
+
 impl Intermediate {
-    pub fn new(tokens: &[Token], hash: Rc<[u8]>) -> Self {
+    pub fn new(tokens: &[Token], hash: Rc<[u8]>, options: Options) -> Self {
         Self {
-            hash: hash,
+            version: Intermediate::version_for(options).into(),
+            hash,
+            modified: 0,
             tokens: tokens.into(),
         }
     }
 
-    pub fn save_for(&self, source: &Source) -> Result<(), CompilerError> {
-        let intermediate_directory = Project::get_intermediate_dir(true)?;
+    // Tokens scanned under other options differ as well, so the options are part of the version
+    fn version_for(options: Options) -> String {
+        let mut version = COMPILER_VERSION.to_string();
+        if options.comments {
+            version.push_str("+comments");
+        }
+        if options.bare_numbers {
+            version.push_str("+bare");
+        }
+        version
+    }
+
+    // Returns the saved bytes, so they can be passed to another thread instead of the tokens
+    pub fn save_for(&self, source: &Source) -> Result<Vec<u8>, CompilerError> {
+        let intermediate_directory = Project::get_intermediate_dir(&source.root, true)?;
         let mut full_path = intermediate_directory.join(source.path.as_ref());
         full_path.set_extension("prt");
 
-        let bytes = bincode::serialize(self)?;
+        let stamped = Intermediate {
+            version: self.version.clone(),
+            hash: self.hash.clone(),
+            modified: source.modified,
+            tokens: self.tokens.clone(),
+        };
+
+        let bytes = bincode::serialize(&stamped)?;
         let mut file = File::create(full_path)?;
         file.write_all(&bytes)?;
 
-        Ok(())
+        Ok(bytes)
     }
-}
-
-impl TryFrom<&Source> for Intermediate {
-    type Error = CompilerError;
 
-    fn try_from(source: &Source) -> Result<Self, Self::Error> {
-        let intermediate_directory = Project::get_intermediate_dir(false)?;
+    // Reads the saved bytes for the source, if they are still valid under the options
+    pub fn read_for(source: &Source, options: Options) -> Result<Vec<u8>, CompilerError> {
+        let intermediate_directory = Project::get_intermediate_dir(&source.root, false)?;
         let mut full_path = intermediate_directory.join(source.path.as_ref());
         full_path.set_extension("prt");
 
         let bytes = fs::read(full_path)?;
-        let intermediate = bincode::deserialize::<Intermediate>(&bytes)?;
+        let header = bincode::deserialize::<Header>(&bytes)?;
+
+        if *header.version != Intermediate::version_for(options) {
+            return Err(CompilerError::None);
+        }
 
-        if source.hash == intermediate.hash {
-            Ok(intermediate)
+        // An untouched source skips hashing, a touched one is still reused if its content is unchanged
+        if source.modified == header.modified {
+            Ok(bytes)
+        } else if source.hash()? == header.hash {
+            // Stamped with the new modified time, so the next build skips hashing again
+            Intermediate::try_from(bytes.as_slice())?.save_for(source)
         } else {
             Err(CompilerError::None)
         }
     }
 }
+
+impl TryFrom<&[u8]> for Intermediate {
+    type Error = CompilerError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(bincode::deserialize::<Intermediate>(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn project(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("stitch-intermediate-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(Project::SOURCE_DIR)).expect("Failed to create the source directory");
+        fs::write(root.join(Project::SOURCE_DIR).join("main.prs"), "[1].").expect("Failed to write the source");
+        root
+    }
+
+    #[test]
+    fn touched_source_is_stamped_again() {
+        let root = project("touched");
+
+        let saved = Source::new(&root, "main.prs", "main.prs", 1).expect("The source should exist");
+        let options = Options::default();
+        Intermediate::new(&[], saved.hash().expect("The source should hash"), options)
+            .save_for(&saved)
+            .expect("The intermediate should save");
+
+        let touched = Source::new(&root, "main.prs", "main.prs", 2).expect("The source should exist");
+        let bytes = Intermediate::read_for(&touched, options).expect("An unchanged content should hit the cache");
+        assert_eq!(Intermediate::try_from(bytes.as_slice()).map(|intermediate| intermediate.modified), Ok(2));
+
+        let bytes = fs::read(root.join(Project::INTERMEDIATE_DIR).join("main.prt")).expect("The intermediate should exist");
+        assert_eq!(bincode::deserialize::<Header>(&bytes).map(|header| header.modified).ok(), Some(2));
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+
+    #[test]
+    fn other_version_or_options_are_rejected() {
+        let root = project("version");
+        let source = Source::new(&root, "main.prs", "main.prs", 1).expect("The source should exist");
+        let hash = source.hash().expect("The source should hash");
+
+        let outdated = Intermediate { version: "0.0.0+tokens.0".into(), ..Intermediate::new(&[], hash.clone(), Options::default()) };
+        outdated.save_for(&source).expect("The intermediate should save");
+        assert!(Intermediate::read_for(&source, Options::default()).is_err());
+
+        let commented = Options { comments: true, ..Options::default() };
+        Intermediate::new(&[], hash, commented).save_for(&source).expect("The intermediate should save");
+        assert!(Intermediate::read_for(&source, Options::default()).is_err());
+        assert!(Intermediate::read_for(&source, commented).is_ok());
+        fs::remove_dir_all(&root).expect("Failed to remove the project");
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::compilation::conjunction::Conjunction;
+use crate::compilation::datatype::Datatype;
+use crate::compilation::phrase::Phrase;
+use crate::compilation::prefix::Prefix;
+use crate::compilation::primitive::Primitive;
+use crate::compilation::statement::Statement;
+use crate::compilation::verb::Verb;
+
+// A linear operation on an evaluation stack, lowered from the statement tree
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Ir {
+    Push(Primitive),
+    PushVoid,
+    Load(Rc<str>),
+    Collect(usize),
+    Call { verb: Verb, arity: usize },
+    // Stores the top of the stack and leaves void in its place, as an assignment evaluates to void
+    Assign(Rc<str>),
+    Declare { names: Rc<[Rc<str>]>, datatype: Option<Datatype>, fixed: bool },
+    Prefix(Prefix),
+    Conjoin(Conjunction),
+    Label(usize),
+    Jump(usize),
+    BranchUnless(usize),
+    Conclude,
+    Pop,
+    BeginNoun { name: Rc<str>, super_type: Option<Datatype> },
+    BeginVerb { name: Rc<str>, hence_type: Option<Datatype>, subject_type: Option<Datatype> },
+    BeginAdjective { name: Rc<str>, subject_type: Datatype },
+    End,
+}
+
+impl fmt::Display for Ir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ir::Push(primitive) => write!(f, "push {primitive}"),
+            Ir::PushVoid => write!(f, "push void"),
+            Ir::Load(name) => write!(f, "load {name}"),
+            Ir::Collect(count) => write!(f, "collect {count}"),
+            Ir::Call { verb, arity } => write!(f, "call {verb}/{arity}"),
+            Ir::Assign(name) => write!(f, "assign {name}"),
+            Ir::Declare { names, datatype, fixed } => {
+                write!(f, "declare {}{}", if *fixed { "fixed " } else { "" }, names.join(", "))?;
+                match datatype {
+                    Some(datatype) => write!(f, ": {datatype}"),
+                    None => Ok(()),
+                }
+            },
+            Ir::Prefix(prefix) => write!(f, "prefix {prefix}"),
+            Ir::Conjoin(conjunction) => write!(f, "conjoin {conjunction}"),
+            Ir::Label(label) => write!(f, "L{label}:"),
+            Ir::Jump(label) => write!(f, "jump L{label}"),
+            Ir::BranchUnless(label) => write!(f, "branch unless L{label}"),
+            Ir::Conclude => write!(f, "conclude"),
+            Ir::Pop => write!(f, "pop"),
+            Ir::BeginNoun { name, super_type: Some(supertype) } => write!(f, "noun {name} is {supertype}"),
+            Ir::BeginNoun { name, .. } => write!(f, "noun {name}"),
+            Ir::BeginVerb { name, hence_type, subject_type } => {
+                write!(f, "verb {name}")?;
+                if let Some(hencetype) = hence_type {
+                    write!(f, " is {hencetype}")?;
+                }
+                if let Some(subjecttype) = subject_type {
+                    write!(f, " for {subjecttype}")?;
+                }
+                Ok(())
+            },
+            Ir::BeginAdjective { name, subject_type } => write!(f, "adjective {name} for {subject_type}"),
+            Ir::End => write!(f, "end"),
+        }
+    }
+}
+
+pub fn lower(statements: &[Statement]) -> Vec<Ir> {
+    let mut lowering = Lowering::default();
+    for statement in statements {
+        lowering.statement(statement);
+    }
+    lowering.operations
+}
+
+#[derive(Default)]
+struct Lowering {
+    operations: Vec<Ir>,
+    labels: usize,
+}
+
+impl Lowering {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Noun { name, super_type, body, .. } => {
+                self.operations.push(Ir::BeginNoun { name: name.clone(), super_type: super_type.clone() });
+                self.body(&body.0);
+            },
+            Statement::Verb { name, hence_type, subject_type, object_types, body, .. } => {
+                self.operations.push(Ir::BeginVerb {
+                    name: name.clone(),
+                    hence_type: hence_type.clone(),
+                    subject_type: subject_type.clone(),
+                });
+                for parameter in object_types.iter() {
+                    self.statement(parameter);
+                }
+                self.body(&body.0);
+            },
+            Statement::Adjective { name, subject_type, body, .. } => {
+                self.operations.push(Ir::BeginAdjective { name: name.clone(), subject_type: subject_type.clone() });
+                self.body(&body.0);
+            },
+            Statement::So { names, datatype, initializer, fixed } => {
+                if let Some(init) = initializer {
+                    self.phrase(init);
+                } else {
+                    self.operations.push(Ir::PushVoid);
+                }
+                self.operations.push(Ir::Declare { names: names.clone(), datatype: datatype.clone(), fixed: *fixed });
+            },
+            Statement::Phrase(phrase) => {
+                self.phrase(phrase);
+                self.operations.push(Ir::Pop);
+            },
+            Statement::Hence(Some(phrase)) => {
+                self.phrase(phrase);
+                self.operations.push(Ir::Conclude);
+            },
+            Statement::Hence(None) => {
+                self.operations.push(Ir::PushVoid);
+                self.operations.push(Ir::Conclude);
+            },
+        }
+    }
+
+    fn body(&mut self, body: &[Statement]) {
+        for statement in body {
+            self.statement(statement);
+        }
+        self.operations.push(Ir::End);
+    }
+
+    fn phrase(&mut self, phrase: &Phrase) {
+        match phrase {
+            Phrase::None => self.operations.push(Ir::PushVoid),
+            Phrase::Primary(Primitive::Variable(name)) => self.operations.push(Ir::Load(name.clone())),
+            Phrase::Primary(Primitive::It) => self.operations.push(Ir::Load("it".into())),
+            Phrase::Primary(Primitive::Collective(phrases)) => {
+                for element in phrases.iter() {
+                    self.phrase(element);
+                }
+                self.operations.push(Ir::Collect(phrases.len()));
+            },
+            Phrase::Primary(primitive) => self.operations.push(Ir::Push(primitive.clone())),
+            // The noun is only evaluated when the adjective holds, otherwise it is void
+            Phrase::Postfix { noun, adjective } => {
+                let otherwise = self.label();
+                let done = self.label();
+                self.phrase(adjective);
+                self.operations.push(Ir::BranchUnless(otherwise));
+                self.phrase(noun);
+                self.operations.push(Ir::Jump(done));
+                self.operations.push(Ir::Label(otherwise));
+                self.operations.push(Ir::PushVoid);
+                self.operations.push(Ir::Label(done));
+            },
+            Phrase::Prefix { prefix, noun } => {
+                self.phrase(noun);
+                self.operations.push(Ir::Prefix(prefix.clone()));
+            },
+            Phrase::Action { subject: Some(sub), verb: Verb::Assign, object: Some(obj) } => match sub.as_ref() {
+                Phrase::Primary(Primitive::Variable(name)) => {
+                    self.phrase(obj);
+                    self.operations.push(Ir::Assign(name.clone()));
+                },
+                _ => {
+                    self.phrase(sub);
+                    self.phrase(obj);
+                    self.operations.push(Ir::Call { verb: Verb::Assign, arity: 2 });
+                },
+            },
+            Phrase::Action { subject, verb, object } => {
+                let operands = [subject, object];
+                for operand in operands.iter().copied().flatten() {
+                    self.phrase(operand);
+                }
+                let arity = operands.iter().filter(|operand| operand.is_some()).count();
+                self.operations.push(Ir::Call { verb: verb.clone(), arity });
+            },
+            Phrase::Condition { left, conjunction, right } => {
+                self.phrase(left);
+                self.phrase(right);
+                self.operations.push(Ir::Conjoin(conjunction.clone()));
+            },
+        }
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::compilation::evaluation::Evaluation;
+    use crate::compilation::intepreter::Intepreter;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    fn parse(source: &str) -> Rc<[Statement]> {
+        let scanner = Scanner::new(source, Rc::from([])).tokenize();
+        Parser::new(scanner.tokens().into())
+            .parse()
+            .expect("The source should parse")
+            .statements()
+    }
+
+    fn number(value: Option<Evaluation>) -> f32 {
+        match value {
+            Some(Evaluation::Number(number)) => number,
+            other => panic!("Expected a number but found {other:?}"),
+        }
+    }
+
+    // Runs the operations on a stack and gives the value each phrase statement leaves to be popped
+    fn execute(operations: &[Ir]) -> Vec<Evaluation> {
+        let mut stack = Vec::new();
+        let mut variables = HashMap::new();
+        let mut popped = Vec::new();
+
+        for operation in operations {
+            match operation {
+                Ir::Push(Primitive::Number(value)) => stack.push(Evaluation::Number(value.parse().unwrap_or_default())),
+                Ir::Push(Primitive::True) => stack.push(Evaluation::Boolean(true)),
+                Ir::Push(Primitive::False) => stack.push(Evaluation::Boolean(false)),
+                Ir::PushVoid => stack.push(Evaluation::Void),
+                Ir::Load(name) => stack.push(variables.get(name).cloned().unwrap_or_default()),
+                Ir::Assign(name) => {
+                    variables.insert(name.clone(), stack.pop().unwrap_or_default());
+                    stack.push(Evaluation::Void);
+                },
+                Ir::Declare { names, .. } => {
+                    let value = stack.pop().unwrap_or_default();
+                    for name in names.iter() {
+                        variables.insert(name.clone(), value.clone());
+                    }
+                },
+                Ir::Call { verb, arity: 2 } => {
+                    let right = number(stack.pop());
+                    let left = number(stack.pop());
+                    stack.push(Evaluation::Number(match verb {
+                        Verb::Add => left + right,
+                        Verb::Subtract => left - right,
+                        Verb::Multiply => left * right,
+                        Verb::Divide => left / right,
+                        verb => panic!("Unexpected verb {verb}"),
+                    }));
+                },
+                Ir::Prefix(Prefix::Negation) => {
+                    let value = number(stack.pop());
+                    stack.push(Evaluation::Number(-value));
+                },
+                Ir::Conjoin(Conjunction::Equal) => {
+                    let right = stack.pop().unwrap_or_default();
+                    let left = stack.pop().unwrap_or_default();
+                    stack.push(Evaluation::Boolean(left.equal(&right).unwrap_or(false)));
+                },
+                Ir::Pop => popped.push(stack.pop().unwrap_or_default()),
+                operation => panic!("Unexpected operation {operation}"),
+            }
+        }
+
+        assert!(stack.is_empty(), "The statements left {stack:?} on the stack");
+        popped
+    }
+
+    #[test]
+    fn lowering_agrees_with_the_interpreter() {
+        let statements = parse("so x is number as [1]. x as [2]. x + [3]. -x. x = [2]. x as x * [4]. x.");
+
+        let mut intepreter = Intepreter::new(false);
+        let evaluated = statements.iter()
+            .map(|statement| intepreter.execute(statement).map(|value| (statement, value)))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("The source should evaluate")
+            .into_iter()
+            .filter(|(statement, _)| matches!(statement, Statement::Phrase(_)))
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+
+        assert_eq!(execute(&lower(&statements)), evaluated);
+    }
+
+    fn lowered(source: &str) -> Vec<String> {
+        lower(&parse(source)).iter().map(|operation| operation.to_string()).collect()
+    }
+
+    #[test]
+    fn arithmetic_is_lowered_operands_first() {
+        assert_eq!(lowered("[1] + [2] * [3]."), [
+            "push [1.0]",
+            "push [2.0]",
+            "push [3.0]",
+            "call */2",
+            "call +/2",
+            "pop",
+        ]);
+    }
+
+    #[test]
+    fn verb_definition_is_lowered() {
+        assert_eq!(lowered("verb half is number for number when so by is number { hence [0] when by = [0]. hence it / by. }"), [
+            "verb half is number for number",
+            "push void",
+            "declare by: number",
+            "load by",
+            "push [0.0]",
+            "conjoin =",
+            "branch unless L0",
+            "push [0.0]",
+            "jump L1",
+            "L0:",
+            "push void",
+            "L1:",
+            "conclude",
+            "load it",
+            "load by",
+            "call //2",
+            "conclude",
+            "end",
+        ]);
+    }
+}
@@ -3,16 +3,21 @@ pub mod intepreter;
 pub mod parser;
 pub mod scanner;
 pub mod errors;
+pub mod token;
 mod source;
+mod analysis;
+mod typecheck;
+mod builtins;
 mod intermediate;
+mod ir;
 mod datatype;
 mod phrase;
 mod environment;
 mod evaluation;
 mod primitive;
+mod routine;
 mod conjunction;
 mod precedent;
 mod prefix;
 mod verb;
 mod statement;
-mod token;
\ No newline at end of file
@@ -1,15 +1,19 @@
 use std::rc::Rc;
 
 use crate::compilation::token::{Token, TokenCategory, TokenType, TokenBuffer};
+use crate::compilation::conjunction::Conjunction;
 use crate::compilation::datatype::Datatype;
 use crate::compilation::phrase::Phrase;
 use crate::compilation::primitive::Primitive;
 use crate::compilation::prefix::Prefix;
 use crate::compilation::statement::{Statement, Statements};
+use crate::compilation::verb::Verb;
 use crate::compilation::errors::CompilerError;
 
 use super::precedent::Precedent;
 
+pub const DEFAULT_MAX_ERRORS: usize = 20;
+
 pub trait ParserState {}
 
 pub struct Parser<State: ParserState = Initial> {
@@ -21,6 +25,7 @@ pub struct Initial;
 #[derive(Debug, Default)]
 pub struct Ready {
     pub tokens : Rc<[Token]>,
+    pub max_errors : usize,
 }
 #[derive(Debug, Default)]
 pub struct Done {
@@ -34,25 +39,43 @@ impl ParserState for Done {}
 impl Parser<Initial> {
     pub fn new(tokens : Rc<[Token]>) -> Parser<Ready> {
         Parser::<Ready> {
-            state: Ready { tokens },
+            state: Ready { tokens, max_errors: DEFAULT_MAX_ERRORS },
         }
     }
 }
 
 impl Parser<Ready> {
+    pub fn max_errors(mut self, max_errors: usize) -> Self {
+        self.state.max_errors = max_errors;
+        self
+    }
+
     pub fn parse(&mut self) -> Result<Parser<Done>, CompilerError> {
-        let mut buffer = self.state.tokens.iter().peekable();
+        let tokens = gather_docs(&self.state.tokens);
+        let mut buffer = tokens.iter().peekable();
 
         let mut statements = Vec::<Statement>::new();
         let mut errors = CompilerError::None;
+        let mut error_count = 0usize;
         
         while !buffer.is_at_end() {
             match handle_prose(&mut buffer) {
                 Ok(statement) => statements.push(statement),
-                Err(error) => errors = errors.add(error),
+                Err(error) => {
+                    // Errors past the cap are only counted
+                    if error_count < self.state.max_errors {
+                        errors = errors.add(error);
+                    }
+                    error_count += 1;
+                },
             }
         }
 
+        if error_count > self.state.max_errors {
+            let msg = format!("({} more errors suppressed)", error_count - self.state.max_errors);
+            errors = errors.add(CompilerError::LexicalError(msg.into()));
+        }
+
         if errors == CompilerError::None {
             Ok(Parser::<Done> {
                 state: Done {
@@ -71,12 +94,52 @@ impl Parser<Done> {
     }
 }
 
+// Comments are only kept when they run line by line up to a noun, verb or adjective,
+// merged into a single comment token in front of it that becomes its doc
+fn gather_docs(tokens: &[Token]) -> Vec<Token> {
+    let mut gathered = Vec::with_capacity(tokens.len());
+    let mut comments = Vec::<&Token>::new();
+    let mut last_line = 0;
+
+    for token in tokens {
+        if token.name == TokenType::Comment {
+            // A comment after code on the same line, or after a blank line, starts afresh
+            if token.line == last_line || comments.last().is_some_and(|last| last.line + 1 != token.line) {
+                comments.clear();
+            }
+            if token.line != last_line {
+                comments.push(token);
+            }
+            continue;
+        }
+
+        let is_definition = matches!(token.name, TokenType::Noun | TokenType::Verb | TokenType::Adjective);
+        if let (true, Some(first), Some(last)) = (is_definition, comments.first(), comments.last()) {
+            if last.line + 1 == token.line {
+                let lexeme = comments.iter()
+                    .map(|comment| comment.lexeme.as_ref())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                gathered.push(Token { lexeme: lexeme.into(), end: last.end, ..(*first).clone() });
+            }
+        }
+
+        comments.clear();
+        last_line = token.line;
+        gathered.push(token.clone());
+    }
+
+    gathered
+}
+
 fn handle_prose<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    if tokens.peek_next(TokenType::Noun)
+    if tokens.peek_next(TokenType::Comment)
+        || tokens.peek_next(TokenType::Noun)
         || tokens.peek_next(TokenType::Verb)
         || tokens.peek_next(TokenType::Adjective)
-        || tokens.peek_next(TokenType::So) {
+        || tokens.peek_next(TokenType::So)
+        || tokens.peek_next(TokenType::Fixed) {
         handle_definition(tokens)
     } else {
         handle_sentence(tokens)
@@ -85,20 +148,31 @@ fn handle_prose<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerE
 
 fn handle_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    let doc = match tokens.get_current() {
+        Some(Token { name: TokenType::Comment, lexeme, .. }) => Some(lexeme.clone()),
+        _ => None,
+    };
+    if doc.is_some() {
+        tokens.advance();
+    }
+
     if tokens.match_next(&[TokenType::Noun]) {
-        return handle_noun_definition(tokens);
+        return handle_noun_definition(tokens, doc);
     } else if tokens.match_next(&[TokenType::Verb]) {
-        return handle_verb_definition(tokens);
+        return handle_verb_definition(tokens, doc);
     } else if tokens.match_next(&[TokenType::Adjective]) {
-        return handle_adjective_definition(tokens);
+        return handle_adjective_definition(tokens, doc);
     } else if tokens.match_next(&[TokenType::So]) {
-        return handle_so_definition(tokens);
+        return handle_so_definition(tokens, false);
+    } else if tokens.match_next(&[TokenType::Fixed]) {
+        tokens.consume(TokenType::So)?;
+        return handle_so_definition(tokens, true);
     }
 
     let token = match tokens.get_current() {
         Some(token) => token.to_owned(),
         None => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
+            return Err(unexpected_end(None, "a definition"));
         }
     };
 
@@ -107,94 +181,73 @@ fn handle_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, Comp
     Err(CompilerError::LexicalError(msg.into()))
 }
 
-fn handle_noun_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
+fn handle_noun_definition<'a, Buffer>(tokens : &mut Buffer, doc: Option<Rc<str>>) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let name_token = match tokens.consume(TokenType::Identifier) {
         Ok(token) => token.to_owned(),
         Err(error) => return Err(error),
     };
 
-    let super_type = if let Ok(_) = tokens.consume(TokenType::Is) {
-        let datatype = match tokens.next() {
-            Some(Token { name: TokenType::Type(datatype), .. }) => datatype.to_owned(),
-            Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned().into()),
-            token => {
-                let msg = format!("Invalid datatype {token:?}");
-                return Err(CompilerError::LexicalError(msg.into()));
-            },
-        };
-
-        Some(datatype)
+    let super_type = if tokens.consume(TokenType::Is).is_ok() {
+        Some(handle_datatype(tokens)?)
     } else {
         None
     };
 
-    if let Err(error) = tokens.consume(TokenType::LeftBrace) {
-        return Err(error);
-    }
+    let opening_line = tokens.consume(TokenType::LeftBrace)?.line;
 
     let mut definitions = Vec::new();
     while !tokens.peek_next(TokenType::RightBrace) && !tokens.is_at_end() {
-        let definition = handle_definition(tokens)?;
+        let definition = match handle_definition(tokens)? {
+            // A member verb acts on the noun it is defined in unless it says otherwise
+            Statement::Verb { name, hence_type, subject_type: None, object_types, body, doc } => Statement::Verb {
+                name,
+                hence_type,
+                subject_type: Some(Datatype::Custom(name_token.lexeme.clone())),
+                object_types,
+                body,
+                doc,
+            },
+            definition => definition,
+        };
         definitions.push(definition);
     }
 
-    if let Err(error) = tokens.consume(TokenType::RightBrace) {
-        return Err(error);
-    }
+    handle_body_end(tokens, opening_line, "noun body")?;
 
     Ok(Statement::Noun {
         name: name_token.lexeme,
         super_type,
         body: Statements(definitions.into()),
+        doc,
     })
 }
 
-fn handle_verb_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
+fn handle_verb_definition<'a, Buffer>(tokens : &mut Buffer, doc: Option<Rc<str>>) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let name_token = match tokens.consume(TokenType::Identifier) {
         Ok(token) => token.to_owned(),
         Err(error) => return Err(error),
     };
 
-    let hence_type = if let Ok(_) = tokens.consume(TokenType::Is) {
-        let datatype = match tokens.next() {
-            Some(Token { name: TokenType::Type(datatype), .. }) => datatype.to_owned(),
-            Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned().into()),
-            token => {
-                let msg = format!("Invalid datatype {token:?}");
-                return Err(CompilerError::LexicalError(msg.into()));
-            },
-        };
-
-        Some(datatype)
+    let hence_type = if tokens.consume(TokenType::Is).is_ok() {
+        Some(handle_datatype(tokens)?)
     } else {
         None
     };
 
-    let subject_type = if let Ok(_) = tokens.consume(TokenType::For) {
-        let datatype = match tokens.next() {
-            Some(Token { name: TokenType::Type(datatype), .. }) => datatype.to_owned(),
-            Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned().into()),
-            token => {
-                let msg = format!("Invalid datatype {token:?}");
-                return Err(CompilerError::LexicalError(msg.into()));
-            },
-        };
-
-        Some(datatype)
+    let subject_type = if tokens.consume(TokenType::For).is_ok() {
+        Some(handle_datatype(tokens)?)
     } else {
         None
     };
 
     let mut parameters = Vec::new();
-    if let Ok(_) = tokens.consume(TokenType::When) {
+    if tokens.consume(TokenType::When).is_ok() {
         parameters = handle_parameters(tokens)?;
     }
 
-    if let Err(error) = tokens.consume(TokenType::LeftBrace) {
-        return Err(error);
-    }
+    let opening_line = tokens.consume(TokenType::LeftBrace)?.line;
 
     let mut sentences = Vec::new();
     while !tokens.peek_next(TokenType::RightBrace) && !tokens.is_at_end() {
@@ -202,9 +255,7 @@ fn handle_verb_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement,
         sentences.push(sentence);
     }
 
-    if let Err(error) = tokens.consume(TokenType::RightBrace) {
-        return Err(error);
-    }
+    handle_body_end(tokens, opening_line, "verb body")?;
 
     Ok(Statement::Verb {
         name: name_token.lexeme,
@@ -212,6 +263,7 @@ fn handle_verb_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement,
         subject_type,
         object_types: parameters.into(),
         body: Statements(sentences.into()),
+        doc,
     })
 }
 
@@ -219,57 +271,43 @@ fn handle_parameters<'a, Buffer>(tokens : &mut Buffer) -> Result<Vec<Statement>,
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let mut declarations = Vec::new();
 
-    if let Err(error) = tokens.consume(TokenType::So) {
-        return Err(error);
-    }
+    tokens.consume(TokenType::So)?;
 
-    declarations.push(handle_so_declaration(tokens)?);
+    declarations.push(handle_so_declaration(tokens, false, false)?);
 
     while tokens.match_next(&[TokenType::Comma]) {
+        // A trailing comma may come before the verb body
+        if tokens.peek_next(TokenType::LeftBrace) { break; }
+
         if tokens.match_next(&[TokenType::And]) {
-            if let Err(error) = tokens.consume(TokenType::So) {
-                return Err(error);
-            }
+            tokens.consume(TokenType::So)?;
         
-            declarations.push(handle_so_declaration(tokens)?);
+            declarations.push(handle_so_declaration(tokens, false, false)?);
             break;
         } else {
-            if let Err(error) = tokens.consume(TokenType::So) {
-                return Err(error);
-            }
+            tokens.consume(TokenType::So)?;
         
-            declarations.push(handle_so_declaration(tokens)?);
+            declarations.push(handle_so_declaration(tokens, false, false)?);
         }
     };
 
     Ok(declarations)
 }
 
-fn handle_adjective_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
+fn handle_adjective_definition<'a, Buffer>(tokens : &mut Buffer, doc: Option<Rc<str>>) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let name_token = match tokens.consume(TokenType::Identifier) {
         Ok(token) => token.to_owned(),
         Err(error) => return Err(error),
     };
 
-    let subject_type = if let Ok(_) = tokens.consume(TokenType::For) {
-        let datatype = match tokens.next() {
-            Some(Token { name: TokenType::Type(datatype), .. }) => datatype.to_owned(),
-            Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned().into()),
-            token => {
-                let msg = format!("Invalid datatype {token:?}");
-                return Err(CompilerError::LexicalError(msg.into()));
-            },
-        };
-
-        datatype
+    let subject_type = if tokens.consume(TokenType::For).is_ok() {
+        handle_datatype(tokens)?
     } else {
         return Err(CompilerError::LexicalError("Adjective missing subject datatype".into()));
     };
 
-    if let Err(error) = tokens.consume(TokenType::LeftBrace) {
-        return Err(error);
-    }
+    let opening_line = tokens.consume(TokenType::LeftBrace)?.line;
 
     let mut sentences = Vec::new();
     while !tokens.peek_next(TokenType::RightBrace) && !tokens.is_at_end() {
@@ -277,52 +315,85 @@ fn handle_adjective_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<State
         sentences.push(sentence);
     }
 
-    if let Err(error) = tokens.consume(TokenType::RightBrace) {
-        return Err(error);
-    }
+    handle_body_end(tokens, opening_line, "adjective body")?;
 
     Ok(Statement::Adjective {
         name: name_token.lexeme,
         subject_type,
         body: Statements(sentences.into()),
+        doc,
     })
 }
 
-fn handle_so_definition<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
+fn handle_so_definition<'a, Buffer>(tokens : &mut Buffer, fixed: bool) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    let definition = handle_so_declaration(tokens)?;
+    let definition = handle_so_declaration(tokens, fixed, true)?;
 
-    if let Err(error) = tokens.consume(TokenType::Dot) {
-        return Err(error);
-    }
+    tokens.consume(TokenType::Dot)?;
 
     Ok(definition)
 }
 
-fn handle_so_declaration<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
+// A datatype may be preceded by a constraint, as in `a nonempty text`
+fn handle_datatype<'a, Buffer>(tokens : &mut Buffer) -> Result<Datatype, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    let name_token = match tokens.consume(TokenType::Identifier) {
-        Ok(token) => token.to_owned(),
-        Err(error) => return Err(error),
-    };
+    tokens.skip_article();
 
-    if let Err(error) = tokens.consume(TokenType::Is) {
-        return Err(error);
+    let constraint = match tokens.get_current() {
+        Some(Token { name: TokenType::Constraint(constraint), .. }) => Some(constraint.to_owned()),
+        _ => None,
+    };
+    if constraint.is_some() {
+        tokens.advance();
     }
 
     let datatype = match tokens.next() {
         Some(Token { name: TokenType::Type(datatype), .. }) => datatype.to_owned(),
-        Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned().into()),
+        Some(Token { name: TokenType::Identifier, lexeme, .. }) => Datatype::Custom(lexeme.to_owned()),
         token => {
             let msg = format!("Invalid datatype {token:?}");
             return Err(CompilerError::LexicalError(msg.into()));
         },
     };
 
+    match constraint {
+        Some(constraint) => Ok(Datatype::Constrained(Box::new(datatype), constraint)),
+        None => Ok(datatype),
+    }
+}
+
+// Verb parameters are separated by commas themselves, so only a so sentence may declare several names
+fn handle_so_declaration<'a, Buffer>(tokens : &mut Buffer, fixed: bool, several: bool) -> Result<Statement, CompilerError>
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    let name_token = match tokens.consume(TokenType::Identifier) {
+        Ok(token) => token.to_owned(),
+        Err(error) => return Err(error),
+    };
+
+    let mut names = vec![name_token.lexeme];
+    while several && (tokens.peek_next(TokenType::Comma) || tokens.peek_next(TokenType::And)) {
+        // Either "x and y" or "x, y, and z", where the last comma is optional
+        if tokens.match_next(&[TokenType::Comma]) {
+            tokens.match_next(&[TokenType::And]);
+        } else {
+            tokens.advance();
+        }
+        names.push(tokens.consume(TokenType::Identifier)?.lexeme.clone());
+    }
+
+    // The datatype may be left out when there is an initializer to infer it from
+    let datatype = if tokens.peek_next(TokenType::As) {
+        None
+    } else {
+        tokens.consume(TokenType::Is)?;
+
+        Some(handle_datatype(tokens)?)
+    };
+
     let token = match tokens.get_current() {
         Some(token) => token.to_owned(),
         None => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
+            return Err(unexpected_end(None, "'as' or the end of the declaration"));
         }
     };
 
@@ -336,23 +407,28 @@ fn handle_so_declaration<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement,
     } else { None };
 
     Ok(Statement::So {
-        name: name_token.lexeme,
+        names: names.into(),
         datatype,
-        initializer
+        initializer,
+        fixed,
     })
 }
 
 fn handle_sentence<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let hence = tokens.match_next(&[TokenType::Hence]);
-    let phrase = handle_phrase(tokens, 0)?;
 
-    if let Err(error) = tokens.consume(TokenType::Dot) {
-        return Err(error);
+    // A bare `hence.` concludes a verb early without a value
+    if hence && tokens.match_next(&[TokenType::Dot]) {
+        return Ok(Statement::Hence(None));
     }
 
+    let phrase = handle_phrase(tokens, 0)?;
+
+    tokens.consume(TokenType::Dot)?;
+
     if hence {
-        Ok(Statement::Hence(phrase))
+        Ok(Statement::Hence(Some(phrase)))
     } else {
         Ok(Statement::Phrase(phrase))
     }
@@ -360,17 +436,17 @@ fn handle_sentence<'a, Buffer>(tokens : &mut Buffer) -> Result<Statement, Compil
 
 fn handle_phrase<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phrase, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    let category = tokens.next().map(|t| TokenCategory::from(t.to_owned()));
+    let current = tokens.next();
+    let line = current.map(|t| t.line);
+    let category = current.map(|t| TokenCategory::from(t.to_owned()));
 
     let mut phrase = match category {
         Some(TokenCategory::Atom(token)) => handle_atom(token)?,
+        Some(TokenCategory::Op(Token { name: TokenType::LeftParen, .. })) => handle_group(tokens)?,
         Some(TokenCategory::Op(prefix)) => handle_prefix(tokens, prefix)?,
-        Some(TokenCategory::EOF) => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
+        Some(TokenCategory::EOF) | None => {
+            return Err(unexpected_end(line, "a noun or phrase"));
         },
-        None => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
-        }
     };
 
     loop {
@@ -386,6 +462,8 @@ fn handle_phrase<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phra
             None => break,
         };
 
+        let (op, is_not) = handle_is_not(tokens, op);
+
         match op.name.precedent() {
             Precedent::Postfix(l_bp) => {
                 if l_bp < precedent { break; }
@@ -397,13 +475,31 @@ fn handle_phrase<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phra
             Precedent::Infix(l_bp, r_bp) => {
                 if l_bp < precedent { break; }
                 tokens.next();
+                if is_not { tokens.next(); }
+
+                // Comparisons and logical operators join two phrases into a condition
+                let conjunction = Conjunction::from(op.name.clone());
+                if conjunction != Conjunction::None {
+                    phrase = Phrase::Condition {
+                        left: Box::new(phrase),
+                        conjunction,
+                        right: Box::new(handle_phrase(tokens, r_bp)?),
+                    };
+
+                    continue;
+                }
 
-                let object = handle_collective(tokens, r_bp)?;
+                // A named verb may be used without an object
+                let object = if op.name == TokenType::Identifier && is_phrase_end(tokens) {
+                    None
+                } else {
+                    Some(Box::new(handle_collective(tokens, r_bp)?))
+                };
     
                 phrase = Phrase::Action {
                     subject: Some(Box::new(phrase)),
-                    verb: op.name.into(),
-                    object: Some(Box::new(object)),
+                    verb: Verb::from(&op),
+                    object,
                 };
 
                 continue;
@@ -415,49 +511,70 @@ fn handle_phrase<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phra
     Ok(phrase)
 }
 
+// `is not` is the word form of the `~` not-equal, wherever a comparison may follow a phrase
+fn handle_is_not<'a, Buffer>(tokens : &mut Buffer, op: Token) -> (Token, bool)
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    if tokens.peek_next_pair(TokenType::Is, TokenType::Not) {
+        (Token { name: TokenType::Tilde, ..op }, true)
+    } else {
+        (op, false)
+    }
+}
+
+fn handle_group<'a, Buffer>(tokens : &mut Buffer) -> Result<Phrase, CompilerError> 
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    let phrase = handle_collective(tokens, 0)?;
+    tokens.consume(TokenType::RightParen)?;
+    Ok(phrase)
+}
+
 fn handle_collective<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phrase, CompilerError> 
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
     let mut phrases = Vec::<Phrase>::new();
-    phrases.push(handle_phrase(tokens, precedent)?);
+    let mut errors = CompilerError::None;
+    handle_element(tokens, precedent, &mut phrases, &mut errors);
 
-    match tokens.get_current().cloned() {
-        None => {
-            phrases.first()
-                .map(|phr| phr.to_owned())
-                .ok_or(CompilerError::LexicalError("Empty collective".into()))
-        }
-        Some(current) => {
-            let token = current;
+    // Every element binds as tightly as the first one, and `, and` or `, or` marks the last element
+    while tokens.match_next(&[TokenType::Comma]) {
+        // A trailing comma may come before the closing parenthesis
+        if tokens.peek_next(TokenType::RightParen) { break; }
 
-            while tokens.match_next(&[TokenType::Comma]) {
+        let is_last = tokens.match_next(&[TokenType::And, TokenType::Or]);
+        handle_element(tokens, precedent, &mut phrases, &mut errors);
 
-                if tokens.match_next(&[TokenType::And, TokenType::Or]) {
-                    if let Precedent::Infix(_, r_bp) = token.name.precedent() {
-                        phrases.push(handle_phrase(tokens, r_bp)?);
-                    } else { 
-                        let msg = format!("[line {}] Error at '{}': {} has a wrong precedent type.", token.line, token.lexeme, token.name);
-                        return Err(CompilerError::LexicalError(msg.into()))
-                    }
+        if is_last { break; }
+    }
+
+    if errors != CompilerError::None {
+        Err(errors)
+    } else if phrases.len() == 1 {
+        Ok(phrases.remove(0))
+    } else {
+        Ok(Phrase::Primary(Primitive::Collective(phrases.into())))
+    }
+}
 
+// A malformed element is skipped up to the comma, parenthesis or dot ending it, so the next elements are still checked
+fn handle_element<'a, Buffer>(tokens : &mut Buffer, precedent: u8, phrases: &mut Vec<Phrase>, errors: &mut CompilerError)
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    match handle_phrase(tokens, precedent) {
+        Ok(phrase) => phrases.push(phrase),
+        Err(error) => {
+            *errors = errors.clone().add(error);
+
+            let mut depth = 0usize;
+            while !tokens.is_at_end() {
+                if tokens.peek_next(TokenType::LeftParen) {
+                    depth += 1;
+                } else if tokens.peek_next(TokenType::RightParen) {
+                    if depth == 0 { break; }
+                    depth -= 1;
+                } else if depth == 0 && (tokens.peek_next(TokenType::Comma) || tokens.peek_next(TokenType::Dot)) {
                     break;
-                } else {
-                    if let Precedent::Infix(_, r_bp) = token.name.precedent() {
-                        phrases.push(handle_phrase(tokens, r_bp)?);
-                    } else { 
-                        let msg = format!("[line {}] Error at '{}': {} has a wrong precedent type.", token.line, token.lexeme, token.name);
-                        return Err(CompilerError::LexicalError(msg.into()))
-                    }
                 }
-            };
-
-            if phrases.len() == 1 {
-                phrases.first()
-                    .map(|phr| phr.to_owned())
-                    .ok_or(CompilerError::LexicalError("Empty collective".into()))
-            } else {
-                Ok(Phrase::Primary(Primitive::Collective(phrases.into())))
+                tokens.advance();
             }
-        }
+        },
     }
 }
 
@@ -466,7 +583,7 @@ fn handle_postfix<'a, Buffer>(tokens : &mut Buffer, noun: Phrase) -> Result<Phra
     let token = match tokens.get_current() {
         Some(token) => token.to_owned(),
         None => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
+            return Err(unexpected_end(None, "a postfix operator"));
         }
     };
 
@@ -491,7 +608,13 @@ fn handle_postfix<'a, Buffer>(tokens : &mut Buffer, noun: Phrase) -> Result<Phra
 
 fn handle_prefix<'a, Buffer>(tokens : &mut Buffer, token: Token) -> Result<Phrase, CompilerError> 
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    if let Precedent::Prefix(bp) = token.name.precedent() {
+    // A minus starting a phrase, including the object right after another operator, is a negation
+    let precedent = match token.name {
+        TokenType::Minus => Prefix::Negation.precedent(),
+        ref name => name.precedent(),
+    };
+
+    if let Precedent::Prefix(bp) = precedent {
         let prefix = match token.name {
             TokenType::Not => Prefix::Not,
             TokenType::Minus => Prefix::Negation,
@@ -506,12 +629,29 @@ fn handle_prefix<'a, Buffer>(tokens : &mut Buffer, token: Token) -> Result<Phras
             let msg = format!("[line {}] Error at '{}': {} is unrecognised prefix operator.", token.line, token.lexeme, token.name);
             return Err(CompilerError::LexicalError(msg.into()))
         }
+
+        // Adjectives may be listed as "the big and red thing" or "the big, heavy, and red thing",
+        // which nest the same as "the big the heavy the red thing"
+        let mut prefixes = vec![prefix];
+        if token.name == TokenType::The {
+            while tokens.peek_next(TokenType::Comma) || tokens.peek_next(TokenType::And) {
+                if tokens.match_next(&[TokenType::Comma]) {
+                    tokens.match_next(&[TokenType::And]);
+                } else {
+                    tokens.advance();
+                }
+                prefixes.push(Prefix::Adjective(tokens.consume(TokenType::Identifier)?.lexeme.clone()));
+            }
+        }
         
-        let phrase = handle_phrase(tokens, bp)?;
-        Ok(Phrase::Prefix {
-            prefix,
-            noun: Box::new(phrase),
-        })
+        let mut phrase = handle_phrase(tokens, bp)?;
+        for prefix in prefixes.into_iter().rev() {
+            phrase = Phrase::Prefix {
+                prefix,
+                noun: Box::new(phrase),
+            };
+        }
+        Ok(phrase)
     } else {
         let msg = format!("[line {}] Error at '{}': {} is invalid prefix operator.", token.line, token.lexeme, token.name);
         Err(CompilerError::LexicalError(msg.into()))
@@ -520,19 +660,18 @@ fn handle_prefix<'a, Buffer>(tokens : &mut Buffer, token: Token) -> Result<Phras
 
 fn handle_adjective<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<Phrase, CompilerError>
     where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
-    let category = tokens.next().map(|t| TokenCategory::from(t.to_owned()));
+    let current = tokens.next();
+    let line = current.map(|t| t.line);
+    let category = current.map(|t| TokenCategory::from(t.to_owned()));
 
     let mut phrase = match category {
         Some(TokenCategory::Atom(token)) => handle_atom(token)?,
         Some(TokenCategory::Op(_)) => {
             return Err(CompilerError::LexicalError("Unsupported adjective as prefix".into()));
         },
-        Some(TokenCategory::EOF) => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
+        Some(TokenCategory::EOF) | None => {
+            return Err(unexpected_end(line, "an adjective"));
         },
-        None => {
-            return Err(CompilerError::LexicalError("Unexpected EOF".into()));
-        }
     };
 
     loop {
@@ -548,10 +687,13 @@ fn handle_adjective<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<P
             None => break,
         };
 
+        let (op, is_not) = handle_is_not(tokens, op);
+
         match op.name.precedent() {
             Precedent::Infix(l_bp, r_bp) => {
                 if l_bp < precedent { break; }
                 tokens.next();
+                if is_not { tokens.next(); }
 
                 let object = handle_adjective(tokens, r_bp)?;
     
@@ -570,6 +712,35 @@ fn handle_adjective<'a, Buffer>(tokens : &mut Buffer, precedent: u8) -> Result<P
     Ok(phrase)
 }
 
+fn is_phrase_end<'a, Buffer>(tokens : &mut Buffer) -> bool
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    tokens.is_at_end()
+        || tokens.peek_next(TokenType::Dot)
+        || tokens.peek_next(TokenType::Comma)
+        || tokens.peek_next(TokenType::RightParen)
+        || tokens.peek_next(TokenType::When)
+}
+
+fn handle_body_end<'a, Buffer>(tokens : &mut Buffer, opening_line: u32, body: &str) -> Result<(), CompilerError>
+    where Buffer: TokenBuffer + Iterator<Item = &'a Token> {
+    if tokens.is_at_end() {
+        let line = tokens.get_current().map(|token| token.line);
+        let expected = format!("'}}' to close {body} started at line {opening_line}");
+        return Err(unexpected_end(line, &expected));
+    }
+
+    tokens.consume(TokenType::RightBrace)?;
+    Ok(())
+}
+
+fn unexpected_end(line: Option<u32>, expected: &str) -> CompilerError {
+    let msg = match line {
+        Some(line) => format!("[line {line}] Unexpected end of file; expected {expected}."),
+        None => format!("Unexpected end of file; expected {expected}."),
+    };
+    CompilerError::LexicalError(msg.into())
+}
+
 fn handle_atom(token : Token) -> Result<Phrase, CompilerError> {
     if token.name == TokenType::It {
         return Ok(Phrase::Primary(Primitive::It));
@@ -595,3 +766,110 @@ fn handle_atom(token : Token) -> Result<Phrase, CompilerError> {
     let msg = format!("At '{}' [line {}], invalid noun or adjective", token.lexeme, token.line);
     Err(CompilerError::LexicalError(msg.into()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::scanner::Scanner;
+
+    fn parse(source: &str) -> Result<Rc<[Statement]>, CompilerError> {
+        let scanner = Scanner::new(source, Rc::from([])).tokenize();
+        Parser::new(scanner.tokens().into()).parse().map(|parser| parser.statements())
+    }
+
+    fn printed(source: &str) -> String {
+        let statements = parse(source).expect("The source should parse");
+        statements.iter().map(|statement| statement.to_string()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn is_not_in_a_phrase() {
+        assert_eq!(printed("\"a\" is not \"b\"."), "\"a\" ~ \"b\".");
+    }
+
+    #[test]
+    fn is_not_in_an_adjective() {
+        assert_eq!(printed("x when y is not [1]."), printed("x when y ~ [1]."));
+    }
+
+    #[test]
+    fn unclosed_verb_body_names_its_start() {
+        let error = parse("\n\nverb greet for number {\n    hence it.\n").expect_err("The verb body is not closed");
+        assert!(error.to_string().contains("[line 5]"), "{error}");
+        assert!(error.to_string().contains("Unexpected end of file; expected '}' to close verb body started at line 3"), "{error}");
+    }
+
+    #[test]
+    fn errors_past_the_cap_are_suppressed() {
+        let scanner = Scanner::new("} } } } }", Rc::from([])).tokenize();
+        let error = Parser::new(scanner.tokens().into())
+            .max_errors(2)
+            .parse()
+            .err()
+            .expect("Every brace is an error");
+
+        match error {
+            CompilerError::MultiError(errors) => {
+                assert_eq!(errors.len(), 3);
+                assert_eq!(errors[2], CompilerError::LexicalError("(3 more errors suppressed)".into()));
+            },
+            error => panic!("Expected several errors but found {error}"),
+        }
+    }
+
+    #[test]
+    fn trailing_comma_in_parameters() {
+        assert_eq!(
+            printed("verb area is number when so width is number, so height is number, { hence width * height. }"),
+            printed("verb area is number when so width is number, so height is number { hence width * height. }"),
+        );
+    }
+
+    #[test]
+    fn trailing_comma_in_a_collective() {
+        assert_eq!(printed("so sizes as ([1], [2], [3],)."), "so sizes as ([1.0], [2.0], [3.0]).");
+        assert_eq!(printed("so sizes as (\n    [1],\n    [2],\n)."), "so sizes as ([1.0], [2.0]).");
+    }
+
+    #[test]
+    fn article_before_a_datatype_is_skipped() {
+        assert_eq!(parse("so x is a number."), parse("so x is number."));
+        assert_eq!(parse("noun dog is an animal { }"), parse("noun dog is animal { }"));
+        assert_eq!(parse("verb bark is a text for an animal { hence \"woof\". }"), parse("verb bark is text for animal { hence \"woof\". }"));
+        assert_eq!(printed("so name is a nonempty text."), "so name is nonempty text.");
+    }
+
+    #[test]
+    fn notion_and_boolean_are_one_datatype() {
+        let statements = parse("so a is notion. so b is boolean.").expect("The source should parse");
+        for statement in statements.iter() {
+            match statement {
+                Statement::So { datatype, .. } => assert_eq!(datatype, &Some(Datatype::Boolean)),
+                statement => panic!("Expected a declaration but found {statement}"),
+            }
+        }
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn every_malformed_element_is_reported() {
+        match parse("so sizes as ([1], +, [3], *).") {
+            Err(CompilerError::MultiError(errors)) => {
+                assert!(errors[0].to_string().contains("'+'"), "{}", errors[0]);
+                assert!(errors[1].to_string().contains("'*'"), "{}", errors[1]);
+            },
+            other => panic!("Expected two errors but found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn listed_adjectives_nest_in_order() {
+        let adjective = |name: &str, noun: Phrase| Phrase::Prefix { prefix: Prefix::Adjective(name.into()), noun: Box::new(noun) };
+        let expected = adjective("big", adjective("red", Phrase::Primary(Primitive::Variable("x".into()))));
+
+        for source in ["the big, red x.", "the big and red x.", "the big the red x."] {
+            assert_eq!(parse(source), Ok(Rc::from([Statement::Phrase(expected.clone())])), "{source}");
+        }
+        assert_eq!(printed("the big, red x."), "the big the red x.");
+    }
+}
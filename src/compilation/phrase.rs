@@ -2,6 +2,7 @@ use std::fmt;
 
 use crate::compilation::primitive::Primitive;
 use crate::compilation::conjunction::Conjunction;
+use crate::compilation::precedent::Precedent;
 use crate::compilation::prefix::Prefix;
 use crate::compilation::verb::Verb;
 
@@ -35,11 +36,84 @@ impl fmt::Display for Phrase {
         match self {
             Phrase::None => write!(f, "{self:?}"),
             Phrase::Primary(primitive) => write!(f, "{primitive}"),
-            Phrase::Postfix { noun, adjective } => write!(f, "({noun} when {adjective})"),
-            Phrase::Prefix { prefix, noun } => write!(f, "(prefix {prefix} {noun})"),
-            Phrase::Action { subject: Some(sub), verb, object: Some(obj) } => write!(f, "{sub} {verb} {obj}"),
+            Phrase::Postfix { noun, adjective } => {
+                // `when` would otherwise bind to the object of a loosely bound verb
+                let noun = noun.grouped_if(|(_, r_bp)| r_bp <= 3);
+                write!(f, "({noun} when {adjective})")
+            },
+            Phrase::Prefix { prefix, noun } => match prefix.precedent() {
+                Precedent::Prefix(bp) => write!(f, "{prefix} {}", noun.grouped_if(|(l_bp, _)| l_bp < bp)),
+                _ => write!(f, "{prefix} {noun}"),
+            },
+            Phrase::Action { subject: Some(sub), verb, object: Some(obj) } => {
+                // Group the nouns that the parser would not have bound to this verb on its own
+                let (sub, obj) = match verb.precedent() {
+                    Precedent::Infix(l_bp, r_bp) => (
+                        sub.grouped_if(|(_, sub_r_bp)| l_bp >= sub_r_bp),
+                        obj.grouped_if(|(obj_l_bp, _)| obj_l_bp < r_bp),
+                    ),
+                    _ => (sub.to_string(), obj.to_string()),
+                };
+                write!(f, "{sub} {verb} {obj}")
+            },
+            Phrase::Action { subject: Some(sub), verb, object: None } => match verb.precedent() {
+                Precedent::Infix(l_bp, _) => write!(f, "{} {verb}", sub.grouped_if(|(_, sub_r_bp)| l_bp >= sub_r_bp)),
+                _ => write!(f, "{sub} {verb}"),
+            },
             Phrase::Action { subject, verb, object } => write!(f, "{subject:?} {verb} {object:?}"),
-            Phrase::Condition { left, conjunction, right } => write!(f, "(condition {left} {conjunction} {right})"),
+            Phrase::Condition { left, conjunction, right } => match conjunction.precedent() {
+                Precedent::Infix(l_bp, r_bp) => write!(f, "{} {conjunction} {}",
+                    left.grouped_if(|(_, left_r_bp)| l_bp >= left_r_bp),
+                    right.grouped_if(|(right_l_bp, _)| right_l_bp < r_bp)),
+                _ => write!(f, "{left} {conjunction} {right}"),
+            },
         }
     }
 }
+
+impl Phrase {
+    // Wraps an action or condition in parentheses when its binding powers need grouping
+    pub fn grouped_if(&self, needs_group: impl FnOnce((u8, u8)) -> bool) -> String {
+        let precedent = match self {
+            Phrase::Action { verb, .. } => verb.precedent(),
+            Phrase::Condition { conjunction, .. } => conjunction.precedent(),
+            _ => Precedent::None,
+        };
+
+        match precedent {
+            Precedent::Infix(l_bp, r_bp) if needs_group((l_bp, r_bp)) => format!("({self})"),
+            _ => self.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    fn printed(source: &str) -> String {
+        let scanner = Scanner::new(source, Rc::from([])).tokenize();
+        let statements = Parser::new(scanner.tokens().into())
+            .parse()
+            .unwrap_or_else(|error| panic!("Failed to parse {source:?}: {error}"))
+            .statements();
+        statements.iter().map(|statement| statement.to_string()).collect()
+    }
+
+    #[test]
+    fn grouping_follows_the_parse_tree() {
+        assert_eq!(printed("a + b * c."), "a + b * c.");
+        assert_eq!(printed("(a + b) * c."), "(a + b) * c.");
+        assert_eq!(printed("a - (b - c)."), "a - (b - c).");
+        assert_eq!(printed("(a - b) - c."), "a - b - c.");
+    }
+
+    #[test]
+    fn redundant_parentheses_are_dropped() {
+        assert_eq!(printed("a + (b * c)."), "a + b * c.");
+        assert_eq!(printed("(a > b) and (c < d)."), "a > b and c < d.");
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt;
 use std::rc::Rc;
 
+use crate::compilation::precedent::Precedent;
+use crate::compilation::token::TokenType;
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Prefix {
     None,
@@ -13,9 +16,21 @@ impl fmt::Display for Prefix {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Prefix::None => write!(f, "{:?}", self),
-            Prefix::Not => write!(f, "!"),
+            Prefix::Not => write!(f, "not"),
             Prefix::Negation => write!(f, "-"),
-            Prefix::Adjective(adj) => write!(f, "{adj}"),
+            Prefix::Adjective(adj) => write!(f, "the {adj}"),
+        }
+    }
+}
+
+impl Prefix {
+    pub fn precedent(&self) -> Precedent {
+        match self {
+            Prefix::None => Precedent::None,
+            Prefix::Not => TokenType::Not.precedent(),
+            // `-` is otherwise a subtraction, so negation binds as tightly as `not` instead
+            Prefix::Negation => TokenType::Not.precedent(),
+            Prefix::Adjective(_) => TokenType::The.precedent(),
         }
     }
 }
@@ -17,8 +17,8 @@ pub enum Primitive {
 impl fmt::Display for Primitive {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Primitive::Number(literal) => write!(f, "{literal}"),
-            Primitive::Text(literal) => write!(f, "{literal}"),
+            Primitive::Number(literal) => write!(f, "[{literal}]"),
+            Primitive::Text(literal) => write!(f, "\"{literal}\""),
             Primitive::True => write!(f, "true"),
             Primitive::False => write!(f, "false"),
             Primitive::It => write!(f, "it"),
@@ -0,0 +1,81 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use crate::compilation::errors::EvaluationError;
+use crate::compilation::evaluation::Evaluation;
+
+pub type NativeInstruction = fn(&Evaluation, &Evaluation) -> Result<Evaluation, EvaluationError>;
+
+#[derive(Clone, Debug)]
+pub struct Routine {
+    pub name: Rc<str>,
+    instruction: NativeInstruction,
+}
+
+// Routines are identified by name, comparing the function pointers is not reliable
+impl PartialEq for Routine {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Routine {}
+
+// Hashed by name as well, so equal routines land in the same bucket
+impl Hash for Routine {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Display for Routine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "verb {}", self.name)
+    }
+}
+
+impl Routine {
+    pub fn new_native(name: &str, instruction: NativeInstruction) -> Self {
+        Self {
+            name: name.into(),
+            instruction,
+        }
+    }
+
+    pub fn execute(&self, subject: &Evaluation, object: &Evaluation) -> Result<Evaluation, EvaluationError> {
+        // A bug in a builtin should fail the program being run, not the whole compiler
+        let result = panic::catch_unwind(AssertUnwindSafe(|| (self.instruction)(subject, object)))
+            .unwrap_or_else(|_| Err(EvaluationError::new(&format!("Builtin {} panicked", self.name))));
+
+        result.map_err(|error| EvaluationError::new(&format!("In verb \"{}\"", self.name)).concat(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::compilation::intepreter::run_source;
+
+    fn broken(_: &Evaluation, _: &Evaluation) -> Result<Evaluation, EvaluationError> {
+        panic!("A bug in the builtin")
+    }
+
+    #[test]
+    fn panicking_builtin_is_an_error() {
+        let routine = Routine::new_native("broken", broken);
+        let error = routine.execute(&Evaluation::Void, &Evaluation::Void).expect_err("The panic is caught");
+        assert!(error.to_string().contains("Builtin broken panicked"));
+    }
+
+    #[test]
+    fn same_builtin_is_equal_and_hashes_alike() {
+        assert_eq!(run_source("reverse = reverse."), Ok(Evaluation::Boolean(true)));
+        assert_eq!(run_source("reverse = sort."), Ok(Evaluation::Boolean(false)));
+
+        let routines = HashSet::from([Routine::new_native("broken", broken), Routine::new_native("broken", broken)]);
+        assert_eq!(routines.len(), 1);
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
+use std::iter::Peekable;
 use std::rc::Rc;
 
 use crate::compilation::source::SourceBuffer;
-use crate::compilation::token::{Token, TokenType, TokenCollection};
+use crate::compilation::token::{Token, TokenType, TokenCollection, TokenPool};
 use crate::compilation::intermediate::Intermediate;
 
 pub trait ScannerState {}
@@ -17,6 +18,13 @@ pub struct Initial;
 pub struct Ready {
     pub source : Rc<str>,
     pub hash : Rc<[u8]>,
+    pub options : Options,
+}
+// The tokenization rules that can be switched on, cached tokens are only reused under the same ones
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub comments : bool,
+    pub bare_numbers : bool,
 }
 #[derive(Debug, Default)]
 pub struct Done {
@@ -33,56 +41,95 @@ impl Scanner<Initial> {
         Scanner::<Ready> {
             state: Ready {
                 source: source.into(),
-                hash: hash.into(),
+                hash,
+                options: Options::default(),
             },
         }
     }
 
-    pub fn from(intermediate: Intermediate) -> Scanner<Done> {
-        Scanner::<Done> {
-            state: Done {
-                intermediate,
-                error_count: 0,
-            },
-        }
-    }
-}
-
-impl Scanner<Ready> {
-    pub fn tokenize(&mut self) -> Scanner<Done> {
-        let mut buffer = SourceBuffer::from(self.state.source.chars());
+    // Tokenizes characters from any reader, the hash is of the content they come from
+    pub fn tokenize_chars(chars: impl Iterator<Item = char>, hash: Rc<[u8]>, options: Options) -> Scanner<Done> {
+        let mut buffer = SourceBuffer::from(chars.peekable());
         let mut line = 1u32;
+        // Nothing but whitespace has been scanned on the current line yet
+        let mut line_start = true;
         
-        let mut tokens : Vec<Token> = Vec::new();
+        let mut tokens = TokenPool::default();
         let mut error_count = 0u32;
         
         let keywords = Token::keywords();
 
+        // A byte order mark is skipped rather than stripped, so the spans still count it
+        buffer.next_if(|&ch| ch == '\u{feff}');
+
         while !buffer.is_at_end() {
             // We are at the beginning of the next lexeme.
             buffer.start();
 
-            let token = scan_token(&mut buffer, &mut line, &keywords, &mut error_count);
+            let token = match scan_token(&mut buffer, &mut line, &keywords, &mut error_count, options.bare_numbers, line_start) {
+                TokenType::Comment if !options.comments => TokenType::None,
+                token => token,
+            };
             let text = buffer.extract();
+            line_start = match token {
+                TokenType::None => line_start || text.ends_with(['\n', '\r']),
+                _ => false,
+            };
             
-            tokens.add(token, Some(text.as_str()), line);
+            tokens.add(token, Some(text.as_str()), line, buffer.start_position());
         }
 
-        tokens.add(TokenType::EOF, None, line);
+        tokens.add(TokenType::EOF, None, line, buffer.position());
 
         Scanner::<Done> {
             state: Done {
-                intermediate: Intermediate::new(tokens.as_slice(), self.state.hash.clone()),
+                intermediate: Intermediate::new(tokens.tokens.as_slice(), hash, options),
                 error_count,
             },
         }
     }
+
+    pub fn from(intermediate: Intermediate) -> Scanner<Done> {
+        Scanner::<Done> {
+            state: Done {
+                intermediate,
+                error_count: 0,
+            },
+        }
+    }
+}
+
+impl Scanner<Ready> {
+    // Comments are dropped unless kept, so that the parser can attach them to definitions as docs
+    pub fn comments(mut self, keep: bool) -> Self {
+        self.state.options.comments = keep;
+        self
+    }
+
+    // Numbers may also be written without brackets, as in `5` and `3.14`
+    pub fn bare_numbers(mut self, allow: bool) -> Self {
+        self.state.options.bare_numbers = allow;
+        self
+    }
+
+    pub fn options(mut self, options: Options) -> Self {
+        self.state.options = options;
+        self
+    }
+
+    pub fn tokenize(&mut self) -> Scanner<Done> {
+        Scanner::tokenize_chars(self.state.source.chars(), self.state.hash.clone(), self.state.options)
+    }
 }
 
 impl Scanner<Done> {
     pub fn intermediate(&self) -> &Intermediate {
         &self.state.intermediate
     }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.state.intermediate.tokens
+    }
     
     pub fn is_err(&self) -> bool {
         self.state.error_count > 0
@@ -93,7 +140,7 @@ impl Scanner<Done> {
     }
 }
 
-fn scan_token(source: &mut SourceBuffer, line: &mut u32, keywords: &HashMap<Rc<str>, TokenType>, error_count: &mut u32) -> TokenType {
+fn scan_token<I: Iterator<Item = char>>(source: &mut SourceBuffer<Peekable<I>>, line: &mut u32, keywords: &HashMap<Rc<str>, TokenType>, error_count: &mut u32, bare_numbers: bool, line_start: bool) -> TokenType {
     if source.is_at_end() {
         return TokenType::EOF; 
     }
@@ -110,18 +157,23 @@ fn scan_token(source: &mut SourceBuffer, line: &mut u32, keywords: &HashMap<Rc<s
         Some('+') => TokenType::Plus,
         Some('*') => TokenType::Star,
         Some('/') => TokenType::Slash,
-        Some('=') => TokenType::Equal,
+        // `==` is accepted as an alias of the single `=` equality
+        Some('=') => { source.match_next('='); TokenType::Equal },
         Some('~') => TokenType::Tilde,
         Some('<') => if source.match_next('=') { TokenType::LessEqual } else { TokenType::Less },
         Some('>') => if source.match_next('=') { TokenType::GreaterEqual } else { TokenType::Greater },
-        Some('!') => skip_comment(source),
+        // `!=` is accepted as an alias of the `~` not-equal after other tokens,
+        // but a line starting with `!` is always a comment as it has been before
+        Some('!') => if !line_start && source.match_next('=') { TokenType::Tilde } else { handle_comment(source) },
         // Skip whitespaces
         Some(' ') => TokenType::None,
-        Some('\r') => TokenType::None,
         Some('\t') => TokenType::None,
-        Some('\n') => { *line += 1; TokenType::None },
+        // `\r\n`, a lone `\r` and a lone `\n` all break a line once
+        Some('\r') => { source.match_next('\n'); next_line(line); TokenType::None },
+        Some('\n') => { next_line(line); TokenType::None },
         Some('\"') => handle_text(source, line, error_count),
         Some('[') => handle_number(source, line, error_count),
+        Some(c) if bare_numbers && is_digit(c) => handle_bare_number(source),
         Some(c) => if is_alpha(c) {
             handle_identifier(source, keywords)
         } else {
@@ -138,10 +190,13 @@ fn scan_token(source: &mut SourceBuffer, line: &mut u32, keywords: &HashMap<Rc<s
     }
 }
 
-fn handle_text(source: &mut SourceBuffer, line : &mut u32, error_count : &mut u32) -> TokenType {
+fn handle_text<I: Iterator<Item = char>>(source: &mut SourceBuffer<I>, line : &mut u32, error_count : &mut u32) -> TokenType {
     while !source.peek_next('\"') && !source.is_at_end() {
-        if source.peek_next('\n') { *line += 1; }
-        source.next();
+        match source.next() {
+            Some('\r') => { source.match_next('\n'); next_line(line); },
+            Some('\n') => next_line(line),
+            _ => {},
+        }
     }
 
     if source.is_at_end() {
@@ -154,11 +209,11 @@ fn handle_text(source: &mut SourceBuffer, line : &mut u32, error_count : &mut u3
     TokenType::Text
 }
 
-fn handle_number(source: &mut SourceBuffer, line : &mut u32, error_count : &mut u32) -> TokenType {
-    while let Some(_) = source.next_if(|&next| is_digit(next)) {}
+fn handle_number<I: Iterator<Item = char>>(source: &mut SourceBuffer<I>, line : &mut u32, error_count : &mut u32) -> TokenType {
+    while source.next_if(|&next| is_digit(next)).is_some() {}
 
     if source.match_next('.') && source.next_if(|&next| is_digit(next)).is_some() {
-        while let Some(_) = source.next_if(|&next| is_digit(next)) {}
+        while source.next_if(|&next| is_digit(next)).is_some() {}
     }
 
     if !source.match_next(']') || source.is_at_end() {
@@ -170,8 +225,20 @@ fn handle_number(source: &mut SourceBuffer, line : &mut u32, error_count : &mut
     TokenType::Number
 }
 
-fn handle_identifier(source: &mut SourceBuffer, keywords: &HashMap<Rc<str>, TokenType>) -> TokenType {
-    while let Some(_) = source.next_if(|&next| is_alphanumeric(next)) {}
+// Without brackets a dot only belongs to the number when a digit follows it, otherwise it ends the sentence
+fn handle_bare_number<I: Iterator<Item = char>>(source: &mut SourceBuffer<Peekable<I>>) -> TokenType {
+    while source.next_if(|&next| is_digit(next)).is_some() {}
+
+    if source.peek_next('.') && source.peek_second().is_some_and(is_digit) {
+        source.next();
+        while source.next_if(|&next| is_digit(next)).is_some() {}
+    }
+
+    TokenType::Number
+}
+
+fn handle_identifier<I: Iterator<Item = char>>(source: &mut SourceBuffer<I>, keywords: &HashMap<Rc<str>, TokenType>) -> TokenType {
+    while source.next_if(|&next| is_alphanumeric(next)).is_some() {}
 
     let text = source.extract();
     keywords.get(text.as_str())
@@ -179,16 +246,20 @@ fn handle_identifier(source: &mut SourceBuffer, keywords: &HashMap<Rc<str>, Toke
         .clone()
 }
 
-fn skip_comment(source: &mut SourceBuffer) -> TokenType {
-    // A comment goes until the end of the line.
-    while !source.match_next('\n') && !source.is_at_end() { 
+fn handle_comment<I: Iterator<Item = char>>(source: &mut SourceBuffer<I>) -> TokenType {
+    // A comment goes until the end of the line, the line break itself is left to count the line.
+    while !source.peek_next('\n') && !source.peek_next('\r') && !source.is_at_end() { 
         source.next();
     }
-    TokenType::None
+    TokenType::Comment
+}
+
+fn next_line(line: &mut u32) {
+    *line = line.saturating_add(1);
 }
 
 fn is_digit(c: char) -> bool {
-    c.is_digit(10)
+    c.is_ascii_digit()
 }
 
 fn is_alpha(c: char) -> bool {
@@ -198,3 +269,114 @@ fn is_alpha(c: char) -> bool {
 fn is_alphanumeric(c: char) -> bool {
     is_alpha(c) || is_digit(c)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::conjunction::Conjunction;
+
+    fn names(source: &str) -> Vec<TokenType> {
+        Scanner::new(source, Rc::from([]))
+            .tokenize()
+            .tokens()
+            .iter()
+            .map(|token| token.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn double_equal_is_one_equality() {
+        assert_eq!(names("a == b"), [TokenType::Identifier, TokenType::Equal, TokenType::Identifier, TokenType::EOF]);
+    }
+
+    #[test]
+    fn bang_equal_is_not_equal() {
+        assert_eq!(names("a != b"), [TokenType::Identifier, TokenType::Tilde, TokenType::Identifier, TokenType::EOF]);
+    }
+
+    #[test]
+    fn lone_equal_is_equality() {
+        assert_eq!(names("a = b"), [TokenType::Identifier, TokenType::Equal, TokenType::Identifier, TokenType::EOF]);
+    }
+
+    #[test]
+    fn line_starting_with_bang_equal_is_a_comment() {
+        assert_eq!(names("!= b\n  != c\n! a comment"), [TokenType::EOF]);
+        assert_eq!(names("!= b\na != c"), [TokenType::Identifier, TokenType::Tilde, TokenType::Identifier, TokenType::EOF]);
+    }
+
+    #[test]
+    fn chars_follow_the_options() {
+        let names = |options: Options| Scanner::tokenize_chars("! note\n5".chars(), Rc::from([]), options)
+            .tokens()
+            .iter()
+            .map(|token| token.name.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names(Options::default()), [TokenType::EOF]);
+        assert_eq!(names(Options { comments: true, bare_numbers: true }), [TokenType::Comment, TokenType::Number, TokenType::EOF]);
+    }
+
+    #[test]
+    fn bang_is_never_a_token() {
+        let commented = Scanner::new("x ! not a negation\nnot x", Rc::from([])).comments(true).tokenize();
+        let names = commented.tokens().iter().map(|token| token.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names, [TokenType::Identifier, TokenType::Comment, TokenType::Not, TokenType::Identifier, TokenType::EOF]);
+        assert_eq!(Conjunction::from(TokenType::Tilde), Conjunction::NotEqual);
+    }
+
+    #[test]
+    fn every_line_ending_breaks_a_line_once() {
+        let lines = |source: &str| Scanner::new(source, Rc::from([]))
+            .tokenize()
+            .tokens()
+            .iter()
+            .map(|token| token.line)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines("a\r\nb\r\nc"), [1, 2, 3, 3]);
+        assert_eq!(lines("a\rb\rc"), [1, 2, 3, 3]);
+        assert_eq!(lines("a\r\n\rb\n\r\nc"), [1, 3, 5, 5]);
+        assert_eq!(lines("\"a\r\nb\" c"), [2, 2, 2]);
+    }
+
+    #[test]
+    fn spans_cover_the_lexemes() {
+        let source = "so name as \"cat\".\nname print.";
+        let scanned = Scanner::new(source, Rc::from([])).tokenize();
+        let spans = scanned.tokens()
+            .iter()
+            .map(|token| &source[token.start..token.end])
+            .collect::<Vec<_>>();
+
+        assert_eq!(spans, ["so", "name", "as", "\"cat\"", ".", "name", "print", ".", ""]);
+        assert_eq!(scanned.tokens().last().map(|token| token.start), Some(source.len()));
+
+        let source = "so café as [1].";
+        let scanned = Scanner::new(source, Rc::from([])).tokenize();
+        assert_eq!(&source[scanned.tokens()[1].start..scanned.tokens()[1].end], "café");
+        assert_eq!(&source[scanned.tokens()[3].start..scanned.tokens()[3].end], "[1]");
+    }
+
+    #[test]
+    fn bare_numbers_sit_beside_bracketed_ones() {
+        let scan = |source: &str| {
+            let scanned = Scanner::new(source, Rc::from([])).bare_numbers(true).tokenize();
+            let tokens = scanned.tokens()
+                .iter()
+                .map(|token| (token.name.clone(), token.lexeme.to_string()))
+                .collect::<Vec<_>>();
+            (tokens, scanned.error_count())
+        };
+        let number = |lexeme: &str| (TokenType::Number, lexeme.to_string());
+        let dot = (TokenType::Dot, ".".to_string());
+        let eof = (TokenType::EOF, String::new());
+
+        assert_eq!(scan("[5] + 3.14."), (vec![number("5.0"), (TokenType::Plus, "+".to_string()), number("3.14"), dot.clone(), eof.clone()], 0));
+        assert_eq!(scan("42."), (vec![number("42.0"), dot.clone(), eof.clone()], 0));
+        assert_eq!(scan("[2.5]."), (vec![number("2.5"), dot, eof], 0));
+
+        let bracketed_only = Scanner::new("3.", Rc::from([])).tokenize();
+        assert_eq!(bracketed_only.error_count(), 1);
+    }
+}
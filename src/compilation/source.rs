@@ -1,51 +1,70 @@
 use std::fs;
-use std::path::PathBuf;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::str::Chars;
 
 use crate::projects::project::Project;
 use crate::compilation::errors::CompilerError;
+use crate::utils::hasher::hash_file;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Source {
+    pub root: Rc<Path>,
     pub path: Rc<str>,
     pub filename: Rc<str>,
-    pub hash: Rc<[u8]>,
+    pub modified: u128,
 }
 
 impl Source {
-    pub fn new(path: &str, filename: &str, hash: &[u8]) -> Result<Self, CompilerError> {
+    pub fn new(root: &Path, path: &str, filename: &str, modified: u128) -> Result<Self, CompilerError> {
         Ok(Self {
+            root: root.into(),
             path: path.into(),
             filename: filename.into(),
-            hash: hash.into(),
+            modified,
         })
     }
 
+    pub fn hash(&self) -> Result<Rc<[u8]>, CompilerError> {
+        Ok(hash_file(self.full_path()?.as_path())?.into())
+    }
+
     pub fn content(&self) -> Result<Rc<str>, CompilerError> {
-        Ok(fs::read_to_string(self.full_path()?)?.as_str().into())
+        let bytes = fs::read(self.full_path()?)?;
+        match String::from_utf8(bytes) {
+            Ok(content) => Ok(content.as_str().into()),
+            Err(error) => {
+                let offset = error.utf8_error().valid_up_to();
+                let msg = format!("{} is not valid UTF-8 at byte {offset}", self.path);
+                Err(CompilerError::SourceError(msg.as_str().into()))
+            },
+        }
     }
 
     pub fn full_path(&self) -> Result<PathBuf, CompilerError> {
-        let source_directory = Project::get_source_dir(false)?;
+        let source_directory = Project::get_source_dir(&self.root, false)?;
         let full_path = source_directory.join(self.path.as_ref());
         Ok(full_path)
     }
 }
 
-pub struct SourceBuffer<'a> {
-    iter: Chars<'a>,
+// Reads from any source of characters, so a file does not have to be loaded whole
+pub struct SourceBuffer<I: Iterator<Item = char>> {
+    iter: I,
     peeked: Option<Option<char>>,
     sub: String,
+    // Byte offsets of the consumed characters and of the start of the current lexeme
+    position: usize,
+    start: usize,
 }
 
-impl<'a> From<Chars<'a>> for SourceBuffer<'a> {
-    fn from(iter: Chars<'a>) -> Self {
-        Self { iter, peeked: None, sub: String::new() }
+impl<I: Iterator<Item = char>> From<I> for SourceBuffer<I> {
+    fn from(iter: I) -> Self {
+        Self { iter, peeked: None, sub: String::new(), position: 0, start: 0 }
     }
 }
 
-impl SourceBuffer<'_> {
+impl<I: Iterator<Item = char>> SourceBuffer<I> {
     pub fn peek(&mut self) -> Option<&char> {
         let iter = &mut self.iter;
         self.peeked.get_or_insert_with(|| iter.next()).as_ref()
@@ -58,7 +77,9 @@ impl SourceBuffer<'_> {
                 // Since we called `self.next()`, we consumed `self.peeked`.
                 assert!(self.peeked.is_none());
                 self.peeked = Some(other);
-                self.sub.pop();
+                if let Some(unread) = self.sub.pop() {
+                    self.position -= unread.len_utf8();
+                }
                 None
             }
         }
@@ -80,14 +101,20 @@ impl SourceBuffer<'_> {
     }
     
     pub fn match_next(&mut self, target: char) -> bool {
-        match self.next_if(|&next| next == target) {
-            Some(_) => true,
-            None => false,
-        }
+        self.next_if(|&next| next == target).is_some()
     }
 
     pub fn start(&mut self) {
         self.sub.clear();
+        self.start = self.position;
+    }
+
+    pub fn start_position(&self) -> usize {
+        self.start
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
     }
 
     pub fn extract(&self) -> String {
@@ -95,8 +122,16 @@ impl SourceBuffer<'_> {
     }
 }
 
+// A second character of lookahead, for when the next one alone cannot decide the token
+impl<I: Iterator<Item = char>> SourceBuffer<Peekable<I>> {
+    pub fn peek_second(&mut self) -> Option<char> {
+        self.peek()?;
+        self.iter.peek().copied()
+    }
+}
+
 // Peekable implementation. It must remember if a None has been seen in the `.peek()` method.
-impl Iterator for SourceBuffer<'_> {
+impl<I: Iterator<Item = char>> Iterator for SourceBuffer<I> {
     type Item = char;
     
     fn next(&mut self) -> Option<Self::Item> {
@@ -107,6 +142,7 @@ impl Iterator for SourceBuffer<'_> {
 
         if let Some(ch) = nxt {
             self.sub.push(ch);
+            self.position += ch.len_utf8();
         }
 
         nxt
@@ -10,6 +10,7 @@ pub enum Statement {
         name: Rc<str>,
         super_type: Option<Datatype>,
         body: Statements,
+        doc: Option<Rc<str>>,
     },
     Verb {
         name: Rc<str>,
@@ -17,46 +18,235 @@ pub enum Statement {
         subject_type: Option<Datatype>,
         object_types: Rc<[Statement]>,
         body: Statements,
+        doc: Option<Rc<str>>,
     },
     Adjective {
         name: Rc<str>,
         subject_type: Datatype,
         body: Statements,
+        doc: Option<Rc<str>>,
     },
     So {
-        name: Rc<str>,
-        datatype: Datatype,
+        names: Rc<[Rc<str>]>,
+        datatype: Option<Datatype>,
         initializer: Option<Phrase>,
+        fixed: bool,
     },
     Phrase(Phrase),
-    Hence(Phrase),
+    Hence(Option<Phrase>),
 }
 
 impl fmt::Display for Statement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(0))
+    }
+}
+
+impl Statement {
+    // Renders the statement as source, with nested bodies indented one level deeper than `depth`
+    fn render(&self, depth: usize) -> String {
+        match self {
+            Statement::Noun { body, doc, .. }
+            | Statement::Verb { body, doc, .. }
+            | Statement::Adjective { body, doc, .. } => {
+                let signature = self.signature().unwrap_or_default();
+                documented(doc, depth, format!("{signature} {}", body.render(depth)))
+            },
+            Statement::So { .. } => format!("{}.", self.declaration()),
+            Statement::Phrase(phrase) => format!("{phrase}."),
+            Statement::Hence(Some(phrase)) => format!("hence {phrase}."),
+            Statement::Hence(None) => "hence.".to_string(),
+        }
+    }
+
+    // The head of a noun, verb or adjective definition, without its body
+    pub fn signature(&self) -> Option<String> {
+        match self {
+            Statement::Noun { name, super_type: Some(supertype), .. } => Some(format!("noun {name} is {supertype}")),
+            Statement::Noun { name, .. } => Some(format!("noun {name}")),
+            Statement::Verb { name, hence_type, subject_type, object_types, .. } => {
+                let mut signature = format!("verb {name}");
+                if let Some(hencetype) = hence_type {
+                    signature.push_str(&format!(" is {hencetype}"));
+                }
+                if let Some(subjecttype) = subject_type {
+                    signature.push_str(&format!(" for {subjecttype}"));
+                }
+                if !object_types.is_empty() {
+                    let parameters = object_types.iter()
+                        .map(|parameter| parameter.declaration())
+                        .collect::<Vec<_>>();
+                    signature.push_str(&format!(" when {}", parameters.join(", ")));
+                }
+                Some(signature)
+            },
+            Statement::Adjective { name, subject_type, .. } => Some(format!("adjective {name} for {subject_type}")),
+            _ => None,
+        }
+    }
+
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            Statement::Noun { doc, .. } | Statement::Verb { doc, .. } | Statement::Adjective { doc, .. } => doc.as_deref(),
+            _ => None,
+        }
+    }
+
+    // A so declaration without its ending dot, as written in verb parameters
+    fn declaration(&self) -> String {
         match self {
-            Statement::Noun { name, super_type: Some(supertype), body } => write!(f, "noun {name} is {supertype} \n{body}"),
-            Statement::Noun { name, super_type, body } => write!(f, "noun {name} is {super_type:?} \n{body}"),
-            Statement::Verb { name, hence_type: Some(hencetype), subject_type: Some(subjecttype), object_types, body } => 
-                write!(f, "verb {name} is {hencetype} for {subjecttype} when {} \n{body}",
-                    object_types.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ")),
-            Statement::Verb { name, hence_type, subject_type, object_types, body } => 
-                write!(f, "verb {name} is {hence_type:?} for {subject_type:?} when {} \n{body}",
-                    object_types.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ")),
-            Statement::Adjective { name, subject_type, body } => write!(f, "adjective {name} for {subject_type} \n{body}"),
-            Statement::So { name, datatype, initializer: Some(init) } => write!(f, "so {name} is {datatype} as {init}"),
-            Statement::So { name, datatype, .. } => write!(f, "so {name} is {datatype}"),
-            Statement::Phrase(phrase) => write!(f, "{phrase}."),
-            Statement::Hence(phrase) => write!(f, "hence {phrase}."),
+            Statement::So { names, datatype, initializer, fixed } => {
+                let fixed = if *fixed { "fixed " } else { "" };
+                let name = match names.as_ref() {
+                    [first, second] => format!("{first} and {second}"),
+                    [rest @ .., last] if !rest.is_empty() => format!("{}, and {last}", rest.join(", ")),
+                    names => names.join(""),
+                };
+                let datatype = match datatype {
+                    Some(datatype) => format!(" is {datatype}"),
+                    None => String::new(),
+                };
+                match initializer {
+                    // The initializer is parsed with the precedence of `as`
+                    Some(init) => format!("{fixed}so {name}{datatype} as {}", init.grouped_if(|(l_bp, _)| l_bp < 4)),
+                    None => format!("{fixed}so {name}{datatype}"),
+                }
+            },
+            other => other.render(0),
         }
     }
 }
 
+// Each line of the doc is written back as a comment above the definition, at the same indentation
+fn documented(doc: &Option<Rc<str>>, depth: usize, definition: String) -> String {
+    match doc {
+        Some(doc) => {
+            let indent = "    ".repeat(depth);
+            let comments = doc.lines()
+                .map(|line| format!("! {line}\n{indent}"))
+                .collect::<String>();
+            format!("{comments}{definition}")
+        },
+        None => definition,
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Statements(pub Rc<[Statement]>);
 
 impl fmt::Display for Statements {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{{\n    {}\n}}", self.0.as_ref().iter().map(|s| s.to_string()).collect::<Vec<_>>().join("\n    "))
+        write!(f, "{}", self.render(0))
+    }
+}
+
+impl Statements {
+    fn render(&self, depth: usize) -> String {
+        if self.0.is_empty() {
+            return "{ }".to_string();
+        }
+
+        let indent = "    ".repeat(depth + 1);
+        let lines = self.0.iter()
+            .map(|statement| format!("{indent}{}", statement.render(depth + 1)))
+            .collect::<Vec<_>>();
+        format!("{{\n{}\n{}}}", lines.join("\n"), "    ".repeat(depth))
+    }
+}
+
+// A whole parsed program, rendered as source with a blank line around each definition block
+pub struct Program(pub Rc<[Statement]>);
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let is_block = |statement: &Statement| matches!(statement,
+            Statement::Noun { .. } | Statement::Verb { .. } | Statement::Adjective { .. });
+
+        for (index, statement) in self.0.iter().enumerate() {
+            if index > 0 {
+                let separator = if is_block(statement) || is_block(&self.0[index - 1]) { "\n\n" } else { "\n" };
+                write!(f, "{separator}")?;
+            }
+            write!(f, "{statement}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Program {
+    // Lists each definition with its signature and doc, the members of a noun one heading deeper
+    pub fn markdown(&self, title: &str) -> String {
+        let mut markdown = format!("# {title}\n");
+        document(&self.0, 2, &mut markdown);
+        markdown
+    }
+}
+
+fn document(statements: &[Statement], level: usize, markdown: &mut String) {
+    for statement in statements {
+        let (kind, name) = match statement {
+            Statement::Noun { name, .. } => ("noun", name),
+            Statement::Verb { name, .. } => ("verb", name),
+            Statement::Adjective { name, .. } => ("adjective", name),
+            _ => continue,
+        };
+
+        let signature = statement.signature().unwrap_or_default();
+        markdown.push_str(&format!("\n{} {kind} {name}\n\n`{signature}`\n", "#".repeat(level)));
+        if let Some(doc) = statement.doc() {
+            markdown.push_str(&format!("\n{doc}\n"));
+        }
+
+        if let Statement::Noun { body, .. } = statement {
+            document(&body.0, level + 1, markdown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    fn parse(source: &str) -> Rc<[Statement]> {
+        let scanner = Scanner::new(source, Rc::from([])).comments(true).tokenize();
+        Parser::new(scanner.tokens().into())
+            .parse()
+            .unwrap_or_else(|error| panic!("Failed to parse {source:?}: {error}"))
+            .statements()
+    }
+
+    #[test]
+    fn program_round_trips() {
+        let source = "so count is number as [1]. so name as \"cat\".\n\
+            ! The pet of the house\n\
+            noun cat { so name is text. verb purr is text { hence \"purr\". } }\n\
+            verb twice is number for number { hence it * [2]. }\n\
+            (count twice) + [1].";
+        let statements = parse(source);
+        let printed = Program(statements.clone()).to_string();
+
+        assert_eq!(parse(&printed), statements);
+        assert!(printed.starts_with("so count is number as [1.0].\nso name as \"cat\".\n\n! The pet of the house\nnoun cat {\n"), "{printed}");
+        assert!(printed.ends_with("}\n\n(count twice) + [1.0].\n"), "{printed}");
+    }
+
+    #[test]
+    fn markdown_lists_definitions_with_their_docs() {
+        let source = "! Doubles a number\n\
+            verb twice is number for number { hence it * [2]. }\n\
+            noun cat {\n\
+            ! Makes the cat content\n\
+            verb purr { hence. }\n\
+            }\n\
+            [1] twice.";
+        let markdown = Program(parse(source)).markdown("Definitions");
+
+        assert!(markdown.starts_with("# Definitions\n"), "{markdown}");
+        assert!(markdown.contains("\n## verb twice\n\n`verb twice is number for number`\n\nDoubles a number\n"), "{markdown}");
+        assert!(markdown.contains("\n## noun cat\n"), "{markdown}");
+        assert!(markdown.contains("\n### verb purr\n\n`verb purr for cat`\n\nMakes the cat content\n"), "{markdown}");
+        assert!(!markdown.contains("[1"), "{markdown}");
     }
 }
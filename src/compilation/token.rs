@@ -1,19 +1,22 @@
 use core::slice::Iter;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::iter::Peekable;
 use std::fmt;
 use std::rc::Rc;
 use serde::{Deserialize, Serialize};
 
-use crate::compilation::datatype::Datatype;
+use crate::compilation::datatype::{Constraint, Datatype};
 use crate::compilation::errors::CompilerError;
 use crate::compilation::precedent::Precedent;
 
-#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(Default, PartialEq, Clone, Debug, Deserialize, Serialize)]
 pub struct Token {
     pub name: TokenType,
     pub lexeme: Rc<str>,
     pub line: u32,
+    // Byte range of the token in its source file
+    pub start: usize,
+    pub end: usize,
 }
 
 impl fmt::Display for Token {
@@ -27,16 +30,25 @@ impl Token {
         HashMap::from([
             ("adjective".into(), TokenType::Adjective),
             ("and".into(), TokenType::And),
+            ("any".into(), TokenType::Type(Datatype::Any)),
             ("as".into(), TokenType::As),
+            // `notion` is the canonical name of the truth type, `boolean` is accepted as an alias
+            ("boolean".into(), TokenType::Type(Datatype::Boolean)),
             ("false".into(), TokenType::False),
+            ("fixed".into(), TokenType::Fixed),
             ("for".into(), TokenType::For),
             ("hence".into(), TokenType::Hence),
             ("is".into(), TokenType::Is),
             ("it".into(), TokenType::It),
+            // `plus` and `minus` are the word forms of `+` and `-`
+            ("minus".into(), TokenType::Minus),
+            ("nonempty".into(), TokenType::Constraint(Constraint::NonEmpty)),
             ("noun".into(), TokenType::Noun),
             ("not".into(), TokenType::Not),
+            ("notion".into(), TokenType::Type(Datatype::Boolean)),
             ("number".into(), TokenType::Type(Datatype::Number)),
             ("or".into(), TokenType::Or),
+            ("plus".into(), TokenType::Plus),
             ("so".into(), TokenType::So),
             ("text".into(), TokenType::Type(Datatype::Text)),
             ("the".into(), TokenType::The),
@@ -44,10 +56,11 @@ impl Token {
             ("true".into(), TokenType::True),
             ("verb".into(), TokenType::Verb),
             ("when".into(), TokenType::When),
-        ]).into()
+        ])
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub enum TokenCategory {
     Atom(Token),
     Op(Token),
@@ -65,6 +78,7 @@ impl From<Token> for TokenCategory {
             TokenType::Text => TokenCategory::Atom(value),
             TokenType::Type(_) => TokenCategory::Atom(value),
             TokenType::It => TokenCategory::Atom(value),
+            TokenType::Comment => TokenCategory::Op(value),
 
             TokenType::LeftParen => TokenCategory::Op(value),
             TokenType::RightParen => TokenCategory::Op(value),
@@ -86,6 +100,8 @@ impl From<Token> for TokenCategory {
             TokenType::Adjective => TokenCategory::Op(value),
             TokenType::And => TokenCategory::Op(value),
             TokenType::As => TokenCategory::Op(value),
+            TokenType::Constraint(_) => TokenCategory::Op(value),
+            TokenType::Fixed => TokenCategory::Op(value),
             TokenType::For => TokenCategory::Op(value),
             TokenType::Hence => TokenCategory::Op(value),
             TokenType::Is => TokenCategory::Op(value),
@@ -104,13 +120,34 @@ impl From<Token> for TokenCategory {
 }
 
 pub trait TokenCollection {
-    fn add(&mut self, token: TokenType, text: Option<&str>, line: u32);
+    fn add(&mut self, token: TokenType, text: Option<&str>, line: u32, start: usize);
 }
 
-impl TokenCollection for Vec<Token> {
-    fn add(&mut self, token: TokenType, text: Option<&str>, line: u32) {
+// Collects tokens so that identical lexemes share a single allocation
+#[derive(Default)]
+pub struct TokenPool {
+    pub tokens: Vec<Token>,
+    lexemes: HashSet<Rc<str>>,
+}
+
+impl TokenPool {
+    fn intern(&mut self, lexeme: String) -> Rc<str> {
+        if let Some(shared) = self.lexemes.get(lexeme.as_str()) {
+            return shared.clone();
+        }
+
+        let shared: Rc<str> = lexeme.into();
+        self.lexemes.insert(shared.clone());
+        shared
+    }
+}
+
+impl TokenCollection for TokenPool {
+    fn add(&mut self, token: TokenType, text: Option<&str>, line: u32, start: usize) {
         if token == TokenType::None { return; }
 
+        let end = start + text.map(str::len).unwrap_or_default();
+
         let literal = if let Some(txt) = text {
             if token == TokenType::Text {
                 let trimmed = txt.trim_matches('\"');
@@ -126,20 +163,28 @@ impl TokenCollection for Vec<Token> {
                 };
                 Some(number)
             }
+            else if token == TokenType::Comment {
+                let trimmed = txt.trim_start_matches('!').trim();
+                Some(trimmed.to_string())
+            }
             else {
                 Some(txt.to_string())
             }
         }
         else { None };
 
-        self.push(Token {
+        let lexeme = self.intern(literal.unwrap_or_default());
+        self.tokens.push(Token {
             name: token, 
-            lexeme: literal.unwrap_or_default().into(),
+            lexeme,
             line,
+            start,
+            end,
         });
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Default, PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
 pub enum TokenType {
     #[default] None,
@@ -157,7 +202,7 @@ pub enum TokenType {
     Star,
     Equal,
     Tilde,
-    //Bang, Used for comment, not tokenized
+    // There is no Bang, `!` starts a comment and `!=` after another token is scanned as Tilde. Negation is spelled `not`.
   
     // One or two character tokens.
     Greater,
@@ -170,12 +215,15 @@ pub enum TokenType {
     Number,
     Text,
     Type(Datatype),
+    Comment,
   
     // Keywords.
     Adjective,
     And,
     As,
+    Constraint(Constraint),
     False,
+    Fixed,
     For,
     Hence,
     Is,
@@ -229,6 +277,7 @@ impl TokenType {
             TokenType::Adjective => Precedent::None,
             TokenType::Comma => Precedent::None,
             TokenType::Dot => Precedent::None,
+            TokenType::Fixed => Precedent::None,
             TokenType::For => Precedent::None,
             TokenType::Hence => Precedent::None,
             TokenType::Is => Precedent::None,
@@ -245,6 +294,8 @@ impl TokenType {
 pub trait TokenBuffer {
     fn is_at_end(&mut self) -> bool;
     fn peek_next(&mut self, target: TokenType) -> bool;
+    fn peek_next_pair(&mut self, first: TokenType, second: TokenType) -> bool;
+    fn skip_article(&mut self);
     fn match_next(&mut self, target: &[TokenType]) -> bool;
     fn consume(&mut self, target: TokenType) -> Result<&Token, CompilerError>;
     fn get_current(&mut self) -> Option<&Token>;
@@ -255,7 +306,7 @@ impl TokenBuffer for Peekable<Iter<'_, Token>> {
     fn is_at_end(&mut self) -> bool {
         let current = self.peek();
         current.is_none() || current
-            .and_then(|&token| Some(token.name == TokenType::EOF))
+            .map(|&token| token.name == TokenType::EOF)
             .unwrap_or_default()
     }
 
@@ -272,6 +323,24 @@ impl TokenBuffer for Peekable<Iter<'_, Token>> {
         }
     }
     
+    fn peek_next_pair(&mut self, first: TokenType, second: TokenType) -> bool {
+        let mut lookahead = self.clone();
+        lookahead.next_if(|next| next.name == first).is_some() && lookahead.peek_next(second)
+    }
+    
+    fn skip_article(&mut self) {
+        // `a` and `an` stay usable as names, they are only articles when a datatype follows them
+        let mut lookahead = self.clone();
+        let is_article = lookahead
+            .next_if(|next| next.name == TokenType::Identifier && matches!(next.lexeme.as_ref(), "a" | "an"))
+            .is_some();
+        let before_datatype = matches!(lookahead.peek().map(|next| &next.name), Some(TokenType::Type(_) | TokenType::Identifier | TokenType::Constraint(_)));
+
+        if is_article && before_datatype {
+            self.next();
+        }
+    }
+
     fn match_next(&mut self, target: &[TokenType]) -> bool {
         for token in target {
             if self.next_if(|&next| next.name == *token).is_some() {
@@ -287,7 +356,11 @@ impl TokenBuffer for Peekable<Iter<'_, Token>> {
             Some(token) => Ok(token),
             None => {
                 if let Some(current) = self.peek() {
-                    let msg = format!("[line {}] Error at '{}': Expect {}.", current.line, current.lexeme, target);
+                    let msg = if current.name == TokenType::EOF {
+                        format!("[line {}] Unexpected end of file; expected {}.", current.line, target)
+                    } else {
+                        format!("[line {}] Error at '{}': Expect {}.", current.line, current.lexeme, target)
+                    };
                     Err(CompilerError::LexicalError(msg.into()))
                 } else {
                     Err(CompilerError::LexicalError("Consuming token at end of file".into()))
@@ -297,10 +370,27 @@ impl TokenBuffer for Peekable<Iter<'_, Token>> {
     }
 
     fn get_current(&mut self) -> Option<&Token> {
-        self.peek().map(|&token| token)
+        self.peek().copied()
     }
 
     fn advance(&mut self) {
         self.next();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_lexemes_share_one_allocation() {
+        let mut pool = TokenPool::default();
+        pool.add(TokenType::Identifier, Some("cat"), 1, 0);
+        pool.add(TokenType::Identifier, Some("dog"), 1, 4);
+        pool.add(TokenType::Identifier, Some("cat"), 2, 8);
+
+        let [cat, dog, again] = &pool.tokens[..] else { panic!("Expected three tokens") };
+        assert!(Rc::ptr_eq(&cat.lexeme, &again.lexeme));
+        assert!(!Rc::ptr_eq(&cat.lexeme, &dog.lexeme));
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::compilation::conjunction::Conjunction;
+use crate::compilation::datatype::Datatype;
+use crate::compilation::errors::{CompilerError, ErrorCode, EvaluationError};
+use crate::compilation::phrase::Phrase;
+use crate::compilation::prefix::Prefix;
+use crate::compilation::primitive::Primitive;
+use crate::compilation::statement::Statement;
+use crate::compilation::verb::Verb;
+
+// Checks the datatypes that can be known without evaluating, anything unknown is left to the interpreter
+pub fn check(statements: &[Statement]) -> Result<(), CompilerError> {
+    let mut checker = Checker::default();
+    collect_definitions(statements, None, &mut checker);
+
+    let mut scope = Scope::default();
+    for statement in statements {
+        checker.statement(statement, &mut scope, None);
+    }
+
+    match checker.error {
+        Some(error) => Err(CompilerError::TypeError(error)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Clone)]
+struct Signature {
+    hence_type: Option<Datatype>,
+    subject_type: Option<Datatype>,
+    object_types: Rc<[Option<Datatype>]>,
+}
+
+// Member verbs are kept apart by their noun, top-level verbs have none
+type VerbKey = (Option<Rc<str>>, Rc<str>);
+
+#[derive(Default)]
+struct Checker {
+    nouns: HashSet<Rc<str>>,
+    verbs: HashMap<VerbKey, Signature>,
+    error: Option<EvaluationError>,
+}
+
+// The known datatypes of the variables in each nested scope, innermost last
+#[derive(Default, Clone)]
+struct Scope {
+    frames: Vec<HashMap<Rc<str>, Option<Datatype>>>,
+}
+
+impl Scope {
+    fn enter(&self) -> Self {
+        let mut inner = self.clone();
+        inner.frames.push(HashMap::new());
+        inner
+    }
+
+    fn declare(&mut self, name: &Rc<str>, datatype: Option<Datatype>) {
+        if self.frames.is_empty() {
+            self.frames.push(HashMap::new());
+        }
+        if let Some(frame) = self.frames.last_mut() {
+            frame.insert(name.clone(), datatype);
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<Datatype> {
+        self.frames.iter().rev()
+            .find_map(|frame| frame.get(name))
+            .cloned()
+            .flatten()
+    }
+}
+
+fn collect_definitions(statements: &[Statement], noun: Option<&Rc<str>>, checker: &mut Checker) {
+    for statement in statements {
+        match statement {
+            Statement::Noun { name, body, .. } => {
+                checker.nouns.insert(name.clone());
+                collect_definitions(&body.0, Some(name), checker);
+            },
+            Statement::Verb { name, hence_type, subject_type, object_types, .. } => {
+                let object_types = object_types.iter()
+                    .map(|parameter| match parameter {
+                        Statement::So { datatype, .. } => datatype.clone(),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                checker.verbs.insert((noun.cloned(), name.clone()), Signature {
+                    hence_type: hence_type.clone(),
+                    subject_type: subject_type.clone(),
+                    object_types: object_types.into(),
+                });
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Checker {
+    fn report(&mut self, detail: &str) {
+        let error = EvaluationError::coded(ErrorCode::MismatchedType, detail);
+        self.error = Some(match self.error.take() {
+            Some(errors) => errors.concat(error),
+            None => error,
+        });
+    }
+
+    fn expect(&mut self, expected: &Datatype, found: &Option<Datatype>, context: &str) {
+        if let Some(found) = found {
+            if expected.parity(found).is_err() {
+                self.report(&format!("Expected {expected} but found {found} {context}"));
+            }
+        }
+    }
+
+    fn resolve(&mut self, datatype: &Datatype, context: &str) {
+        match datatype {
+            Datatype::Custom(name) if !self.nouns.contains(name) => {
+                self.report(&format!("Unknown datatype \"{name}\" {context}"));
+            },
+            Datatype::Constrained(datatype, _) => self.resolve(datatype, context),
+            _ => {},
+        }
+    }
+
+    // The hence type is that of the verb or adjective whose body is being checked, if any
+    fn statement(&mut self, statement: &Statement, scope: &mut Scope, hence_type: Option<&Datatype>) {
+        match statement {
+            Statement::Noun { name, super_type, body, .. } => {
+                if let Some(supertype) = super_type {
+                    self.resolve(supertype, &format!("in noun {name}"));
+                }
+                let mut inner = scope.enter();
+                for definition in body.0.iter() {
+                    self.statement(definition, &mut inner, None);
+                }
+            },
+            Statement::Verb { name, hence_type, subject_type, object_types, body, .. } => {
+                let context = format!("in verb {name}");
+                for datatype in [hence_type, subject_type].into_iter().flatten() {
+                    self.resolve(datatype, &context);
+                }
+
+                let mut inner = scope.enter();
+                inner.declare(&"it".into(), subject_type.clone());
+                for parameter in object_types.iter() {
+                    self.statement(parameter, &mut inner, None);
+                }
+                for sentence in body.0.iter() {
+                    self.statement(sentence, &mut inner, hence_type.as_ref());
+                }
+            },
+            Statement::Adjective { name, subject_type, body, .. } => {
+                self.resolve(subject_type, &format!("in adjective {name}"));
+
+                let mut inner = scope.enter();
+                inner.declare(&"it".into(), Some(subject_type.clone()));
+                for sentence in body.0.iter() {
+                    self.statement(sentence, &mut inner, Some(&Datatype::Boolean));
+                }
+            },
+            Statement::So { names, datatype, initializer, .. } => {
+                let context = format!("in so {}", names.join(", "));
+                if let Some(datatype) = datatype {
+                    self.resolve(datatype, &context);
+                }
+
+                // A collective with one element per name is spread over the names, anything else is shared
+                let found = match initializer {
+                    Some(Phrase::Primary(Primitive::Collective(elements))) if names.len() > 1 && elements.len() == names.len() => elements.iter()
+                        .map(|element| self.phrase(element, scope))
+                        .collect(),
+                    Some(phrase) => vec![self.phrase(phrase, scope); names.len()],
+                    None => vec![None; names.len()],
+                };
+                for (name, found) in names.iter().zip(found) {
+                    if let Some(datatype) = datatype {
+                        self.expect(datatype, &found, &context);
+                    }
+                    scope.declare(name, datatype.clone().or(found));
+                }
+            },
+            Statement::Phrase(phrase) => {
+                self.phrase(phrase, scope);
+            },
+            Statement::Hence(Some(phrase)) => {
+                let concluded = self.phrase(phrase, scope);
+                if let Some(hencetype) = hence_type {
+                    self.expect(hencetype, &concluded, &format!("in \"{statement}\""));
+                }
+            },
+            Statement::Hence(None) => {},
+        }
+    }
+
+    fn phrase(&mut self, phrase: &Phrase, scope: &Scope) -> Option<Datatype> {
+        match phrase {
+            Phrase::None => None,
+            Phrase::Primary(Primitive::Number(_)) => Some(Datatype::Number),
+            Phrase::Primary(Primitive::Text(_)) => Some(Datatype::Text),
+            Phrase::Primary(Primitive::True | Primitive::False) => Some(Datatype::Boolean),
+            Phrase::Primary(Primitive::Variable(name)) => scope.get(name),
+            Phrase::Primary(Primitive::It) => scope.get("it"),
+            Phrase::Primary(Primitive::Collective(phrases)) => {
+                for element in phrases.iter() {
+                    self.phrase(element, scope);
+                }
+                None
+            },
+            Phrase::Postfix { noun, adjective } => {
+                self.phrase(adjective, scope);
+                self.phrase(noun, scope)
+            },
+            Phrase::Prefix { prefix, noun } => {
+                let found = self.phrase(noun, scope);
+                let context = format!("in \"{phrase}\"");
+                match prefix {
+                    Prefix::Not => {
+                        self.expect(&Datatype::Boolean, &found, &context);
+                        Some(Datatype::Boolean)
+                    },
+                    Prefix::Negation => {
+                        self.expect(&Datatype::Number, &found, &context);
+                        Some(Datatype::Number)
+                    },
+                    Prefix::Adjective(_) | Prefix::None => found,
+                }
+            },
+            Phrase::Condition { left, conjunction, right } => {
+                let left_type = self.phrase(left, scope);
+                let right_type = self.phrase(right, scope);
+                if let (Conjunction::Equal | Conjunction::NotEqual, Some(left_type), Some(right_type)) = (conjunction, &left_type, &right_type) {
+                    if left_type.parity(right_type).is_err() {
+                        self.report(&format!("Cannot compare {left_type} with {right_type} in \"{phrase}\""));
+                    }
+                }
+                Some(Datatype::Boolean)
+            },
+            Phrase::Action { subject, verb, object } => {
+                let subject_type = subject.as_ref().and_then(|sub| self.phrase(sub, scope));
+                let object_types = match object.as_deref() {
+                    Some(Phrase::Primary(Primitive::Collective(elements))) => elements.iter()
+                        .map(|element| self.phrase(element, scope))
+                        .collect(),
+                    Some(obj) => vec![self.phrase(obj, scope)],
+                    None => Vec::new(),
+                };
+                let object_type = match object.as_deref() {
+                    Some(Phrase::Primary(Primitive::Collective(_))) | None => None,
+                    Some(_) => object_types.first().cloned().flatten(),
+                };
+                let context = format!("in \"{phrase}\"");
+
+                match verb {
+                    Verb::Assign => {
+                        if let Some(Phrase::Primary(Primitive::Variable(name))) = subject.as_deref() {
+                            if let Some(declared) = scope.get(name) {
+                                self.expect(&declared, &object_type, &context);
+                            }
+                        }
+                        object_type
+                    },
+                    Verb::Action(name) => match self.signature(name, &subject_type) {
+                        Some(signature) => {
+                            if let Some(expected) = &signature.subject_type {
+                                self.expect(expected, &subject_type, &context);
+                            }
+                            self.arguments(&signature, object_type, &object_types, &context);
+                            signature.hence_type
+                        },
+                        None => None,
+                    },
+                    Verb::Add | Verb::Subtract | Verb::Multiply | Verb::Divide => {
+                        self.expect(&Datatype::Number, &subject_type, &context);
+                        self.expect(&Datatype::Number, &object_type, &context);
+                        Some(Datatype::Number)
+                    },
+                    Verb::None => None,
+                }
+            },
+        }
+    }
+
+    // A member verb of the subject's noun comes first, then a top-level verb, and with neither
+    // a verb is only checked when a single noun defines it
+    fn signature(&self, name: &Rc<str>, subject_type: &Option<Datatype>) -> Option<Signature> {
+        let noun = match subject_type {
+            Some(Datatype::Custom(noun)) => Some(noun.clone()),
+            _ => None,
+        };
+
+        let mut candidates = self.verbs.iter().filter(|((_, verb), _)| verb == name);
+        self.verbs.get(&(noun, name.clone()))
+            .or_else(|| self.verbs.get(&(None, name.clone())))
+            .or_else(|| match (candidates.next(), candidates.next()) {
+                (Some((_, signature)), None) => Some(signature),
+                _ => None,
+            })
+            .cloned()
+    }
+
+    // A verb with several parameters takes them as a collective, one element each
+    fn arguments(&mut self, signature: &Signature, object_type: Option<Datatype>, element_types: &[Option<Datatype>], context: &str) {
+        match signature.object_types.as_ref() {
+            [Some(expected)] => self.expect(expected, &object_type, context),
+            expected if expected.len() > 1 && expected.len() == element_types.len() => {
+                for (expected, found) in expected.iter().zip(element_types.iter()) {
+                    if let Some(expected) = expected {
+                        self.expect(expected, found, context);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compilation::parser::Parser;
+    use crate::compilation::scanner::Scanner;
+
+    fn check_source(source: &str) -> Result<(), CompilerError> {
+        let scanner = Scanner::new(source, Rc::from([])).tokenize();
+        let statements = Parser::new(scanner.tokens().into())
+            .parse()
+            .expect("The source should parse")
+            .statements();
+        check(&statements)
+    }
+
+    const SPEAKERS: &str = "\
+        noun cat { verb speak is text { hence \"meow\". } }
+        noun dog { verb speak is number { hence [1]. } }
+        verb speak is notion for number { hence true. }
+        so tom is cat. so rex is dog.";
+
+    #[test]
+    fn member_verbs_keep_their_own_signature() {
+        let source = format!("{SPEAKERS}
+            so purr is text as (tom speak).
+            so bark is number as (rex speak).
+            so said is notion as ([1] speak).");
+        assert_eq!(check_source(&source), Ok(()));
+    }
+
+    #[test]
+    fn member_verb_is_chosen_by_subject() {
+        let source = format!("{SPEAKERS} so purr is number as (tom speak).");
+        assert!(check_source(&source).is_err());
+    }
+
+    #[test]
+    fn any_parameter_takes_number_and_text() {
+        let source = "verb show is text when so value is any { hence \"shown\". }
+            so first is text as ([1] show [2]).
+            so second is text as ([1] show \"two\").";
+        assert_eq!(check_source(source), Ok(()));
+
+        let strict = "verb show is text when so value is number { hence \"shown\". } so shown is text as ([1] show \"two\").";
+        assert!(check_source(strict).is_err());
+    }
+}
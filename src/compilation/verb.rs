@@ -2,7 +2,8 @@ use std::convert::From;
 use std::fmt;
 use std::rc::Rc;
 
-use crate::compilation::token::TokenType;
+use crate::compilation::precedent::Precedent;
+use crate::compilation::token::{Token, TokenType};
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Verb {
@@ -23,7 +24,7 @@ impl fmt::Display for Verb {
             Verb::Multiply => write!(f, "*"),
             Verb::Subtract => write!(f, "-"),
             Verb::Add => write!(f, "+"),
-            Verb::Assign => write!(f, "="),
+            Verb::Assign => write!(f, "as"),
             Verb::Action(verb) => write!(f, "{verb}"),
         }
     }
@@ -42,3 +43,26 @@ impl From<TokenType> for Verb {
         }
     }
 }
+
+impl From<&Token> for Verb {
+    fn from(token: &Token) -> Self {
+        match token.name {
+            TokenType::Identifier => Verb::Action(token.lexeme.clone()),
+            _ => token.name.clone().into(),
+        }
+    }
+}
+
+impl Verb {
+    pub fn precedent(&self) -> Precedent {
+        match self {
+            Verb::None => Precedent::None,
+            Verb::Divide => TokenType::Slash.precedent(),
+            Verb::Multiply => TokenType::Star.precedent(),
+            Verb::Subtract => TokenType::Minus.precedent(),
+            Verb::Add => TokenType::Plus.precedent(),
+            Verb::Assign => TokenType::As.precedent(),
+            Verb::Action(_) => TokenType::Identifier.precedent(),
+        }
+    }
+}
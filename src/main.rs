@@ -1,5 +1,10 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use stitch::compilation::parser::DEFAULT_MAX_ERRORS;
+use stitch::projects::project::Project;
 
+use crate::color::ColorChoice;
+
+mod color;
 mod commands;
 
 #[derive(Parser, Debug)]
@@ -7,28 +12,107 @@ mod commands;
 struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Whether errors are printed with ANSI colors
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Number of threads used to tokenize the sources, defaults to the available parallelism
+    #[arg(long, short, global = true)]
+    jobs: Option<usize>,
+    /// Fail when the analysis finds anything to warn about
+    #[arg(long, global = true)]
+    strict: bool,
+    /// Accept numbers written without brackets, as in `5` and `3.14`
+    #[arg(long, global = true)]
+    bare_numbers: bool,
+    /// Work on the project in the `sandbox` directory instead of the current one
+    #[arg(long, global = true)]
+    sandbox: bool,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     New { project: String },
-    Build,
-    Clean,
-    Rebuild,
-    Run,
+    Build {
+        /// Maximum number of parse errors reported before the rest are suppressed
+        #[arg(long, default_value_t = DEFAULT_MAX_ERRORS)]
+        max_errors: usize,
+        /// Print the program lowered to the given target instead of evaluating it
+        #[arg(long, value_enum)]
+        target: Option<Target>,
+    },
+    /// Parse and type-check the project without evaluating it
+    Check,
+    Clean {
+        /// Remove the intermediate directory even if it holds other files or is unexpectedly large
+        #[arg(long)]
+        force: bool,
+    },
+    Rebuild {
+        /// Maximum number of parse errors reported before the rest are suppressed
+        #[arg(long, default_value_t = DEFAULT_MAX_ERRORS)]
+        max_errors: usize,
+        /// Remove the intermediate directory even if it holds other files or is unexpectedly large
+        #[arg(long)]
+        force: bool,
+    },
+    Run {
+        /// Print the evaluated values of the top-level phrases in the given format
+        #[arg(long, value_enum)]
+        emit: Option<Emit>,
+        /// Print each statement, verb and assignment to stderr as it is evaluated
+        #[arg(long)]
+        trace: bool,
+        /// Print the top-level environment to stderr after evaluating
+        #[arg(long)]
+        dump_env: bool,
+    },
+    /// Scan the sources in memory and evaluate them without touching the intermediate files
+    BuildAndRun {
+        /// Maximum number of parse errors reported before the rest are suppressed
+        #[arg(long, default_value_t = DEFAULT_MAX_ERRORS)]
+        max_errors: usize,
+        /// Print each statement, verb and assignment to stderr as it is evaluated
+        #[arg(long)]
+        trace: bool,
+    },
+    /// Write the nouns, verbs and adjectives of the project with their docs as markdown
+    Doc,
     Test,
+    Explain { code: String },
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum Target {
+    /// A linear list of stack operations
+    Ir,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum Emit {
+    /// Print the parsed program back as source, without evaluating it
+    Ast,
+    ValuesJson,
 }
 
 fn main() {
     let args = Args::parse();
+    color::init(args.color);
+    let jobs = args.jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|jobs| jobs.get()))
+        .unwrap_or(1);
+    let root = Project::default_root(args.sandbox);
 
     match args.command {
-        Some(Commands::New { project }) => commands::create_project(project.as_str()),
-        Some(Commands::Build) => commands::build_project(),
-        Some(Commands::Clean) => commands::clean_project(),
-        Some(Commands::Rebuild) => commands::clean_and_build_project(),
-        Some(Commands::Run) => commands::run_project(),
+        Some(Commands::New { project }) => commands::create_project(&root, project.as_str()),
+        Some(Commands::Build { max_errors, target }) => commands::build_project(&root, max_errors, target, jobs, args.strict, args.bare_numbers),
+        Some(Commands::Check) => commands::check_project(&root, jobs, args.strict, args.bare_numbers),
+        Some(Commands::Clean { force }) => commands::clean_project(&root, force),
+        Some(Commands::Rebuild { max_errors, force }) => commands::clean_and_build_project(&root, max_errors, force, jobs, args.strict, args.bare_numbers),
+        Some(Commands::Run { emit, trace, dump_env }) => commands::run_project(&root, emit, trace, dump_env, jobs, args.strict, args.bare_numbers),
+        Some(Commands::BuildAndRun { max_errors, trace }) => commands::build_and_run_project(&root, max_errors, trace, args.strict, args.bare_numbers),
+        Some(Commands::Doc) => commands::document_project(&root, jobs, args.strict, args.bare_numbers),
         Some(Commands::Test) => commands::test_project(),
+        Some(Commands::Explain { code }) => commands::explain_error(code.as_str()),
 
         None => eprintln!("Unknown command entered"),
     };
@@ -3,7 +3,6 @@ use std::path::Path;
 use std::io::Write;
 use serde::Serialize;
 
-use crate::resourses::SANDBOX;
 use crate::compilation::errors::CompilerError;
 use crate::projects::project::Project;
 
@@ -15,14 +14,14 @@ pub struct Config {
 impl Config {
     const FILENAME: &'static str = "Book.toml";
 
-    pub fn create(project_name: &str) -> Result<(), CompilerError> {
+    pub fn create(root: &Path, project_name: &str) -> Result<(), CompilerError> {
         let config = Self {
             project: Project::new(project_name),
         };
 
         let toml = toml::to_string(&config).unwrap();
 
-        let mut file = File::create(Path::new(SANDBOX).join(Config::FILENAME))?;
+        let mut file = File::create(root.join(Config::FILENAME))?;
         write!(file, "{}", toml)?;
         Ok(())
     }
@@ -1,3 +1,4 @@
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -15,6 +16,7 @@ pub struct Project {
 impl Project {
     pub const SOURCE_DIR: &'static str = "source";
     pub const INTERMEDIATE_DIR: &'static str = "intermediate";
+    pub const DOCS_DIR: &'static str = "docs";
     const ENTRY_FILE: &'static str = "main.prs";
 
     pub fn new(name: &str) -> Self {
@@ -24,8 +26,17 @@ impl Project {
         }
     }
 
-    pub fn create_entrypoint() -> Result<(), CompilerError> {
-        let source_path = Self::get_source_dir(true)?;
+    // The project root used by the command line, the working directory unless the sandbox is asked for
+    pub fn default_root(sandbox: bool) -> PathBuf {
+        if sandbox {
+            PathBuf::from(SANDBOX)
+        } else {
+            env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+        }
+    }
+
+    pub fn create_entrypoint(root: &Path) -> Result<(), CompilerError> {
+        let source_path = Self::get_source_dir(root, true)?;
 
         let template_filepath = Path::new(RESOURCES_DIR).join(TEMPLATE_DIR).join(Project::ENTRY_FILE);
         let entry_filepath = source_path.join(Project::ENTRY_FILE);
@@ -36,8 +47,8 @@ impl Project {
         Ok(())
     }
 
-    pub fn get_source_dir(create_if_not_exist: bool) -> Result<PathBuf, CompilerError> {
-        let source_path = Path::new(SANDBOX).join(Project::SOURCE_DIR);
+    pub fn get_source_dir(root: &Path, create_if_not_exist: bool) -> Result<PathBuf, CompilerError> {
+        let source_path = root.join(Project::SOURCE_DIR);
         if !source_path.exists() {
             if create_if_not_exist {
                 fs::create_dir(&source_path)?;
@@ -49,11 +60,12 @@ impl Project {
         Ok(source_path)
     }
 
-    pub fn get_intermediate_dir(create_if_not_exist: bool) -> Result<PathBuf, CompilerError> {
-        let intermediate_path = Path::new(SANDBOX).join(Project::INTERMEDIATE_DIR);
+    pub fn get_intermediate_dir(root: &Path, create_if_not_exist: bool) -> Result<PathBuf, CompilerError> {
+        let intermediate_path = root.join(Project::INTERMEDIATE_DIR);
         if !intermediate_path.exists() {
             if create_if_not_exist {
-                fs::create_dir(&intermediate_path)?;
+                // Sources tokenized in parallel may all try to create it at once
+                fs::create_dir_all(&intermediate_path)?;
             } else {
                 return Err(CompilerError::SourceError(Rc::from("Intermediate directory not exist")));
             }
@@ -61,4 +73,17 @@ impl Project {
     
         Ok(intermediate_path)
     }
+
+    pub fn get_docs_dir(root: &Path, create_if_not_exist: bool) -> Result<PathBuf, CompilerError> {
+        let docs_path = root.join(Project::DOCS_DIR);
+        if !docs_path.exists() {
+            if create_if_not_exist {
+                fs::create_dir(&docs_path)?;
+            } else {
+                return Err(CompilerError::SourceError(Rc::from("Docs directory not exist")));
+            }
+        }
+    
+        Ok(docs_path)
+    }
 }
@@ -1,5 +1,5 @@
 
-pub const RESOURCES_DIR: &'static str = "resources";
-pub const TEMPLATE_DIR: &'static str = "template";
+pub const RESOURCES_DIR: &str = "resources";
+pub const TEMPLATE_DIR: &str = "template";
 
-pub const SANDBOX: &'static str = "sandbox";
+pub const SANDBOX: &str = "sandbox";
@@ -7,10 +7,14 @@ use crate::compilation::errors::CompilerError;
 
 pub fn hash_file(path: &Path) -> Result<Vec<u8>, CompilerError> {
     let mut hasher = Sha256::new();
-    let mut file = File::open(&path)?;
+    let mut file = File::open(path)?;
 
     io::copy(&mut file, &mut hasher)?;
     let hash = hasher.finalize().to_vec();
 
     Ok(hash)
-}
\ No newline at end of file
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes).to_vec()
+}
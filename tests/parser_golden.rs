@@ -0,0 +1,58 @@
+use std::rc::Rc;
+
+use stitch::compilation::parser::Parser;
+use stitch::compilation::scanner::Scanner;
+
+// Each snippet is parsed and printed back as source, one statement per line
+fn print(source: &str) -> String {
+    let scanner = Scanner::new(source, Rc::from([])).comments(true).tokenize();
+    let parser = Parser::new(scanner.tokens().into())
+        .parse()
+        .unwrap_or_else(|error| panic!("Failed to parse {source:?}: {error}"));
+    parser.statements()
+        .iter()
+        .map(|statement| statement.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// The golden string is also parsed again, so the printed form stays valid source
+fn assert_golden(source: &str, golden: &str) {
+    assert_eq!(print(source), golden);
+    assert_eq!(print(golden), golden);
+}
+
+#[test]
+fn noun() {
+    assert_golden("noun cat is any { so name is text. }", "noun cat is any {\n    so name is text.\n}");
+}
+
+#[test]
+fn verb() {
+    assert_golden("verb greet is text for text when so loud is notion { hence it. }", "verb greet is text for text when so loud is notion {\n    hence it.\n}");
+}
+
+#[test]
+fn adjective() {
+    assert_golden("adjective big for number { hence it > [10]. }", "adjective big for number {\n    hence it > [10.0].\n}");
+}
+
+#[test]
+fn so() {
+    assert_golden("fixed so width, height is number as ([1], [2]).", "fixed so width and height is number as ([1.0], [2.0]).");
+}
+
+#[test]
+fn phrase() {
+    assert_golden("[1] + [2] * -[3].", "[1.0] + [2.0] * - [3.0].");
+}
+
+#[test]
+fn hence() {
+    assert_golden("verb stop { hence. }", "verb stop {\n    hence.\n}");
+}
+
+#[test]
+fn documented() {
+    assert_golden("! The pet of the house\nnoun cat { }", "! The pet of the house\nnoun cat { }");
+}